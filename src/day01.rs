@@ -1,58 +1,203 @@
 use crate::solution::Solution;
-use nom::character::complete::{digit1, line_ending, space1};
+use nom::character::complete::{i64, space1};
 use nom::multi::separated_list1;
-use nom::sequence::separated_pair;
 use nom::{IResult, Parser};
 use nom_supreme::error::ErrorTree;
 use nom_supreme::final_parser::final_parser;
 use nom_supreme::ParserExt;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
 
 pub struct AdventPuzzle;
 
 impl Solution for AdventPuzzle {
     fn part1(input: &str) -> String {
-        let (mut left, mut right) = get_list(input).expect("Advent Puzzle parsing failed");
-        left.sort();
-        right.sort();
-
-        let total: usize = left
-            .into_iter()
-            .zip(right)
-            .map(|(left, right)| left.abs_diff(right))
-            .sum();
-
+        let columns = get_columns(input).expect("Advent Puzzle parsing failed");
+        // Each distance fits in a u64 but a long list of them might not
+        let total: u128 = sorted_distances(&columns).map(u128::from).sum();
         total.to_string()
     }
 
     fn part2(input: &str) -> String {
-        let (left, right) = get_list(input).expect("Advent Puzzle parsing failed");
+        let columns = get_columns(input).expect("Advent Puzzle parsing failed");
+        similarity_score(&columns[0], &columns[1]).to_string()
+    }
+
+    fn explain_part1(input: &str) -> Option<String> {
+        let columns = get_columns(input).expect("Advent Puzzle parsing failed");
+        Some(ListStatistics::new(&columns).to_string())
+    }
+
+    fn explain_part2(input: &str) -> Option<String> {
+        Self::explain_part1(input)
+    }
+}
 
-        let total: usize = left
+/// Distances between the first two columns, pairing them up smallest first
+fn sorted_distances(columns: &Columns) -> impl Iterator<Item = u64> + '_ {
+    columns[0]
+        .iter()
+        .zip(&columns[1])
+        .map(|(left, right)| left.abs_diff(*right))
+}
+
+/// Each left value multiplied by how often it appears on the right, summed. Done in `i128` since
+/// even one large value appearing twice overflows an `i64`.
+fn similarity_score(left: &[i64], right: &[i64]) -> i128 {
+    let mut right_counts: HashMap<i64, i128> = HashMap::new();
+    for r in right {
+        *right_counts.entry(*r).or_default() += 1;
+    }
+
+    left.iter()
+        .map(|l| right_counts.get(l).copied().unwrap_or_default() * i128::from(*l))
+        .sum()
+}
+
+/// Added as floats since the two middle values could overflow an `i64` together
+fn median(sorted: &[i64]) -> Option<f64> {
+    let middle = sorted.len() / 2;
+    match sorted.len() {
+        0 => None,
+        len if len % 2 == 0 => Some((sorted[middle - 1] as f64 + sorted[middle] as f64) / 2.0),
+        _ => Some(sorted[middle] as f64),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct ListStatistics {
+    rows: usize,
+    medians: Vec<Option<f64>>,
+    /// How many distances have each number of digits, `0` counts distances of zero
+    distances: BTreeMap<u32, usize>,
+    /// Distinct values found in both of the first two columns
+    overlap: usize,
+}
+
+impl ListStatistics {
+    fn new(columns: &Columns) -> Self {
+        let mut distances = BTreeMap::new();
+        for distance in sorted_distances(columns) {
+            let digits = distance.checked_ilog10().map_or(0, |log| log + 1);
+            *distances.entry(digits).or_default() += 1;
+        }
+
+        let left: HashSet<_> = columns[0].iter().collect();
+        let overlap = columns[1]
             .iter()
-            .map(|l| right.iter().copied().filter(|r| r == l).count() * l)
-            .sum();
+            .collect::<HashSet<_>>()
+            .intersection(&left)
+            .count();
 
-        total.to_string()
+        Self {
+            rows: columns[0].len(),
+            medians: columns.iter().map(|column| median(column)).collect(),
+            distances,
+            overlap,
+        }
+    }
+}
+
+impl Display for ListStatistics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Rows: {}", self.rows)?;
+        for (i, median) in self.medians.iter().enumerate() {
+            match median {
+                Some(median) => writeln!(f, "Median of column {}: {median}", i + 1)?,
+                None => writeln!(f, "Median of column {}: none", i + 1)?,
+            }
+        }
+        writeln!(f, "Distances between sorted columns 1 and 2:")?;
+        for (digits, count) in &self.distances {
+            match digits {
+                0 => writeln!(f, "  0: {count}")?,
+                _ => writeln!(
+                    f,
+                    "  {}-{}: {count}",
+                    10u64.pow(digits - 1),
+                    10u64.pow(*digits) - 1
+                )?,
+            }
+        }
+        write!(f, "Values in both columns 1 and 2: {}", self.overlap)
     }
 }
 
-fn parse_pair(input: &str) -> IResult<&str, (usize, usize), ErrorTree<&str>> {
-    separated_pair(
-        digit1.map_res(str::parse).context("Should be a number"),
-        space1.context("Pairs of numbers should be separated by spaces"),
-        digit1.map_res(str::parse).context("Should be a number"),
+#[derive(Debug)]
+enum ListError<'a> {
+    Parse {
+        line: usize,
+        error: ErrorTree<&'a str>,
+    },
+    ColumnCount {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    TooFewColumns,
+}
+
+impl Display for ListError<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ListError::Parse { line, error } => write!(f, "line {line}: {error}"),
+            ListError::ColumnCount {
+                line,
+                expected,
+                found,
+            } => write!(f, "line {line}: expected {expected} columns, found {found}"),
+            ListError::TooFewColumns => write!(f, "need at least two columns"),
+        }
+    }
+}
+
+impl Error for ListError<'_> {}
+
+/// Numbers from the input, one sorted list per column
+type Columns = Vec<Vec<i64>>;
+
+fn parse_row(input: &str) -> IResult<&str, Vec<i64>, ErrorTree<&str>> {
+    separated_list1(
+        space1.context("Numbers should be separated by spaces"),
+        i64.context("Should be a number"),
     )
     .parse(input)
 }
 
-fn parse_list(input: &str) -> IResult<&str, (Vec<usize>, Vec<usize>), ErrorTree<&str>> {
-    separated_list1(line_ending, parse_pair)
-        .map(|list| list.into_iter().unzip())
-        .parse(input)
-}
+/// Reads the input a line at a time into its columns, then sorts each column once so the
+/// distances and medians can share it.
+///
+/// This doesn't stream: pairing the columns up and taking medians both need every value, sorted,
+/// so the whole of each column has to be held anyway.
+fn get_columns(input: &str) -> Result<Columns, ListError<'_>> {
+    let mut columns: Columns = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let row = final_parser(parse_row.all_consuming())(line.trim_end())
+            .map_err(|error| ListError::Parse { line: i + 1, error })?;
+
+        if columns.is_empty() {
+            columns = vec![Vec::new(); row.len()];
+        }
+        if row.len() != columns.len() {
+            return Err(ListError::ColumnCount {
+                line: i + 1,
+                expected: columns.len(),
+                found: row.len(),
+            });
+        }
+        for (column, value) in columns.iter_mut().zip(row) {
+            column.push(value);
+        }
+    }
 
-fn get_list(input: &str) -> Result<(Vec<usize>, Vec<usize>), ErrorTree<&str>> {
-    final_parser(parse_list.terminated(line_ending.opt()).all_consuming())(input)
+    if columns.len() < 2 {
+        return Err(ListError::TooFewColumns);
+    }
+    for column in &mut columns {
+        column.sort_unstable();
+    }
+    Ok(columns)
 }
 
 #[cfg(test)]
@@ -80,4 +225,96 @@ mod test {
 3   3";
         assert_eq!(AdventPuzzle::part2(input), "31");
     }
+
+    #[test]
+    fn test_get_columns() {
+        let columns = get_columns("-3   4   10\n4   -3   0\n").unwrap();
+        assert_eq!(columns, vec![vec![-3, 4], vec![-3, 4], vec![0, 10]]);
+
+        assert!(matches!(
+            get_columns("1 2 3\n4 5"),
+            Err(ListError::ColumnCount {
+                line: 2,
+                expected: 3,
+                found: 2
+            })
+        ));
+        assert!(matches!(get_columns("1\n2"), Err(ListError::TooFewColumns)));
+        assert!(matches!(
+            get_columns("1 2\n3 x"),
+            Err(ListError::Parse { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_signed_values() {
+        let input = "-3   4
+4   -3
+0   0";
+        assert_eq!(AdventPuzzle::part1(input), "0");
+        assert_eq!(AdventPuzzle::part2(input), "1");
+    }
+
+    #[test]
+    fn test_list_statistics() {
+        let input = "3   4
+4   3
+2   5
+1   3
+3   9
+3   3";
+        let statistics = ListStatistics::new(&get_columns(input).unwrap());
+        assert_eq!(
+            statistics,
+            ListStatistics {
+                rows: 6,
+                medians: vec![Some(3.0), Some(3.5)],
+                distances: BTreeMap::from([(0, 1), (1, 5)]),
+                overlap: 2,
+            }
+        );
+        assert_eq!(
+            statistics.to_string(),
+            "Rows: 6
+Median of column 1: 3
+Median of column 2: 3.5
+Distances between sorted columns 1 and 2:
+  0: 1
+  1-9: 5
+Values in both columns 1 and 2: 2"
+        );
+    }
+
+    #[test]
+    fn test_large_values() {
+        let input = "5000000000000000000   5000000000000000000
+5000000000000000000   5000000000000000000";
+        assert_eq!(AdventPuzzle::part2(input), "20000000000000000000");
+
+        let input = "-9223372036854775808   9223372036854775807
+-9223372036854775808   9223372036854775807";
+        assert_eq!(
+            AdventPuzzle::part1(input),
+            (2 * u64::MAX as u128).to_string()
+        );
+    }
+
+    #[test]
+    fn test_median_extremes() {
+        assert_eq!(median(&[i64::MAX, i64::MAX]), Some(i64::MAX as f64));
+        assert_eq!(median(&[i64::MIN, i64::MAX]), Some(0.0));
+        assert_eq!(median(&[]), None);
+    }
+
+    #[test]
+    fn test_part2_large_input() {
+        // Every value from 0 to 999 appears 200 times in each column, far too slow if each left
+        // value rescans the whole right column
+        let input = (0..200_000)
+            .map(|i| format!("{}   {}", i % 1000, (i * 7) % 1000))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let expected = 200 * 200 * (0..1000).sum::<usize>();
+        assert_eq!(AdventPuzzle::part2(&input), expected.to_string());
+    }
 }
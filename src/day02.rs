@@ -1,74 +1,257 @@
+use crate::solution::Solution;
 use nom::character::complete::{digit1, space1};
-use nom::IResult;
 use nom::multi::separated_list1;
+use nom::IResult;
 use nom_supreme::error::ErrorTree;
 use nom_supreme::final_parser::final_parser;
 use nom_supreme::ParserExt;
-use crate::solution::Solution;
+use std::cmp::{Ordering, Reverse};
+use std::fmt::{Display, Formatter};
 
 pub struct AdventPuzzle;
 
 impl Solution for AdventPuzzle {
     fn part1(input: &str) -> String {
-        let count = input.lines()
+        let count = input
+            .lines()
             .map(|line| get_list(line).expect("Could not parse input"))
-            .filter(|list| is_safely_ascending(list) || is_safely_descending(list))
+            .filter(|list| Dampener::STRICT.is_safe(list))
             .count();
         count.to_string()
     }
 
     fn part2(input: &str) -> String {
-
-        let count = input.lines()
+        let count = input
+            .lines()
             .map(|line| get_list(line).expect("Could not parse input"))
-            .filter(|list| is_loosely_safely_ascending(list) || is_loosely_safely_descending(list))
+            .filter(|list| Dampener::PROBLEM_DAMPENER.is_safe(list))
             .count();
         count.to_string()
     }
+
+    fn explain_part1(input: &str) -> Option<String> {
+        Some(explain(input, Dampener::STRICT))
+    }
+
+    fn explain_part2(input: &str) -> Option<String> {
+        Some(explain(input, Dampener::PROBLEM_DAMPENER))
+    }
 }
 
-fn safe_ascent(left: usize, right: usize) -> bool {
-    right > left && right - left <= 3
+fn explain(input: &str, dampener: Dampener) -> String {
+    input
+        .lines()
+        .map(|line| {
+            let list = get_list(line).expect("Could not parse input");
+            format!("{line}: {}", dampener.classify(&list))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
-fn safe_descent(left: usize, right: usize) -> bool {
-    left > right && left - right <= 3
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Direction {
+    Ascending,
+    Descending,
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Direction::Ascending => write!(f, "ascending"),
+            Direction::Descending => write!(f, "descending"),
+        }
+    }
 }
 
-fn is_safely_ascending(list: &[usize]) -> bool {
-    list.windows(2).all(|w| safe_ascent(w[0] , w[1]))
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum UnsafeReason {
+    Flat,
+    StepTooSmall,
+    StepTooLarge,
+    DirectionChange,
 }
 
-fn is_safely_descending(list: &[usize]) -> bool {
-    list.windows(2).all(|w| safe_descent(w[0] , w[1]))
+impl Display for UnsafeReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnsafeReason::Flat => write!(f, "flat"),
+            UnsafeReason::StepTooSmall => write!(f, "step too small"),
+            UnsafeReason::StepTooLarge => write!(f, "step too large"),
+            UnsafeReason::DirectionChange => write!(f, "direction change"),
+        }
+    }
 }
 
+/// The first step in a report that breaks the rules, going from level `index - 1` to `index`
+#[derive(Clone, PartialEq, Debug)]
+struct UnsafeStep {
+    index: usize,
+    from: usize,
+    to: usize,
+    reason: UnsafeReason,
+}
 
-fn is_loosely_safely_ascending(list: &[usize]) -> bool {
-    // Not very memory efficient but 🤷🏻‍♂️
-    let mut new_list_left: Vec<_> = list.iter().copied().collect();
-    let mut new_list_right: Vec<_> = list.iter().copied().collect();
+#[derive(Clone, PartialEq, Debug)]
+enum ReportSafety {
+    Safe(Direction),
+    Dampened {
+        direction: Direction,
+        removed: Vec<usize>,
+    },
+    Unsafe(UnsafeStep),
+}
 
-    // remove first bad value
-    if let Some(i) = list.windows(2).position(|w| !safe_ascent(w[0], w[1])) {
-        new_list_left.remove(i);
-        new_list_right.remove(i + 1usize);
+impl Display for ReportSafety {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportSafety::Safe(direction) => write!(f, "safe, {direction}"),
+            ReportSafety::Dampened { direction, removed } => {
+                let removed: Vec<_> = removed.iter().map(usize::to_string).collect();
+                write!(
+                    f,
+                    "safe with dampener, {direction} after removing index {}",
+                    removed.join(", ")
+                )
+            }
+            ReportSafety::Unsafe(step) => write!(
+                f,
+                "unsafe, {} from {} at index {} to {} at index {}",
+                step.reason,
+                step.from,
+                step.index - 1,
+                step.to,
+                step.index
+            ),
+        }
     }
+}
 
-    is_safely_ascending(&new_list_left) || is_safely_ascending(&new_list_right)
+/// How far apart neighbouring levels may be, and how many levels can be removed to make a report
+/// safe
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct Dampener {
+    min_step: usize,
+    max_step: usize,
+    tolerance: usize,
 }
 
-fn is_loosely_safely_descending(list: &[usize]) -> bool {
-    let mut new_list_left: Vec<_> = list.iter().copied().collect();
-    let mut new_list_right: Vec<_> = list.iter().copied().collect();
+impl Dampener {
+    const STRICT: Dampener = Dampener {
+        min_step: 1,
+        max_step: 3,
+        tolerance: 0,
+    };
+
+    const PROBLEM_DAMPENER: Dampener = Dampener {
+        min_step: 1,
+        max_step: 3,
+        tolerance: 1,
+    };
+
+    fn is_safe_step(&self, direction: Direction, left: usize, right: usize) -> bool {
+        let step = match direction {
+            Direction::Ascending => right.checked_sub(left),
+            Direction::Descending => left.checked_sub(right),
+        };
+        step.is_some_and(|step| step >= self.min_step && step <= self.max_step)
+    }
+
+    /// The levels to remove for the rest to be safe in the given direction, if it can be done
+    /// without going over the tolerance.
+    ///
+    /// `removals[i]` is the fewest levels removed before `i` such that `i` is kept and every kept
+    /// level up to it is safe, along with the kept level before it. Only the previous
+    /// `tolerance + 1` levels can come before `i` without going over the tolerance, so each level
+    /// is only compared to a handful of others. Ties keep the later level, so earlier levels are
+    /// the ones removed.
+    fn levels_to_remove(&self, list: &[usize], direction: Direction) -> Option<Vec<usize>> {
+        let mut removals: Vec<(usize, Option<usize>)> = Vec::with_capacity(list.len());
+        for (i, level) in list.iter().enumerate() {
+            let (removed, Reverse(previous)) = (i.saturating_sub(self.tolerance + 1)..i)
+                .filter(|j| self.is_safe_step(direction, list[*j], *level))
+                .map(|j| (removals[j].0 + (i - j - 1), Reverse(Some(j))))
+                .fold((i, Reverse(None)), |fewest, removal| fewest.min(removal));
+            removals.push((removed, previous));
+        }
+
+        let Some((removed, Reverse(last))) = removals
+            .iter()
+            .enumerate()
+            .map(|(i, (removed, _))| (removed + (list.len() - 1 - i), Reverse(i)))
+            .min()
+        else {
+            return Some(Vec::new());
+        };
+        if removed > self.tolerance {
+            return None;
+        }
 
-    // remove first bad value
-    if let Some(i) = list.windows(2).position(|w| !safe_descent(w[0], w[1])) {
-        new_list_left.remove(i);
-        new_list_right.remove(i + 1usize);
+        let mut kept = vec![false; list.len()];
+        let mut current = Some(last);
+        while let Some(i) = current {
+            kept[i] = true;
+            current = removals[i].1;
+        }
+        Some((0..list.len()).filter(|i| !kept[*i]).collect())
     }
 
-    is_safely_descending(&new_list_left) || is_safely_descending(&new_list_right)
+    fn is_safe(&self, list: &[usize]) -> bool {
+        [Direction::Ascending, Direction::Descending]
+            .into_iter()
+            .any(|direction| self.levels_to_remove(list, direction).is_some())
+    }
+
+    /// The first step that breaks the rules, the direction is set by the first step that moves
+    fn first_unsafe_step(&self, list: &[usize]) -> Option<UnsafeStep> {
+        let mut report_direction = None;
+        list.windows(2).enumerate().find_map(|(i, w)| {
+            let step = w[0].abs_diff(w[1]);
+            let direction = match w[0].cmp(&w[1]) {
+                Ordering::Less => Some(Direction::Ascending),
+                Ordering::Greater => Some(Direction::Descending),
+                Ordering::Equal => None,
+            };
+            let reason = if step == 0 && self.min_step > 0 {
+                Some(UnsafeReason::Flat)
+            } else if step < self.min_step {
+                Some(UnsafeReason::StepTooSmall)
+            } else if step > self.max_step {
+                Some(UnsafeReason::StepTooLarge)
+            } else if direction.is_some()
+                && report_direction.is_some()
+                && direction != report_direction
+            {
+                Some(UnsafeReason::DirectionChange)
+            } else {
+                None
+            };
+            report_direction = report_direction.or(direction);
+            reason.map(|reason| UnsafeStep {
+                index: i + 1,
+                from: w[0],
+                to: w[1],
+                reason,
+            })
+        })
+    }
+
+    fn classify(&self, list: &[usize]) -> ReportSafety {
+        let mut options: Vec<_> = [Direction::Ascending, Direction::Descending]
+            .into_iter()
+            .filter_map(|direction| Some((self.levels_to_remove(list, direction)?, direction)))
+            .collect();
+        options.sort_by_key(|(removed, _)| removed.len());
+
+        match options.into_iter().next() {
+            Some((removed, direction)) if removed.is_empty() => ReportSafety::Safe(direction),
+            Some((removed, direction)) => ReportSafety::Dampened { direction, removed },
+            None => ReportSafety::Unsafe(
+                self.first_unsafe_step(list)
+                    .expect("An unsafe report should have an unsafe step"),
+            ),
+        }
+    }
 }
 
 fn parse_list(input: &str) -> IResult<&str, Vec<usize>, ErrorTree<&str>> {
@@ -83,6 +266,130 @@ fn get_list(input: &str) -> Result<Vec<usize>, ErrorTree<&str>> {
 mod test {
     use super::*;
 
+    /// Checks every way of removing up to `tolerance` levels
+    fn brute_force_is_safe(list: &[usize], dampener: Dampener) -> bool {
+        let strictly_safe = [Direction::Ascending, Direction::Descending]
+            .into_iter()
+            .any(|direction| {
+                list.windows(2)
+                    .all(|w| dampener.is_safe_step(direction, w[0], w[1]))
+            });
+        if strictly_safe || dampener.tolerance == 0 {
+            return strictly_safe;
+        }
+
+        let looser = Dampener {
+            tolerance: dampener.tolerance - 1,
+            ..dampener
+        };
+        (0..list.len()).any(|i| {
+            let mut shorter = list.to_vec();
+            shorter.remove(i);
+            brute_force_is_safe(&shorter, looser)
+        })
+    }
+
+    #[test]
+    fn test_dampener_matches_brute_force() {
+        // Small linear congruential generator so the reports are the same every run
+        let mut seed: u64 = 2024;
+        let mut next = |limit: u64| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+            ((seed >> 33) % limit) as usize
+        };
+
+        for _ in 0..2000 {
+            let len = next(9);
+            let mut list = vec![next(20)];
+            for _ in 0..len {
+                let last = *list.last().unwrap();
+                list.push((last + next(9)).saturating_sub(4));
+            }
+
+            for (min_step, max_step) in [(1, 3), (2, 5), (0, 1)] {
+                for tolerance in 0..3 {
+                    let dampener = Dampener {
+                        min_step,
+                        max_step,
+                        tolerance,
+                    };
+                    assert_eq!(
+                        dampener.is_safe(&list),
+                        brute_force_is_safe(&list, dampener),
+                        "{list:?} with {dampener:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dampener_removes_either_side_of_bad_step() {
+        // The bad step is 5 -> 1, but it's the 5 that has to go
+        assert!(Dampener::PROBLEM_DAMPENER.is_safe(&[1, 5, 2, 3, 4]));
+        // Here only the first level has to go
+        assert!(Dampener::PROBLEM_DAMPENER.is_safe(&[5, 1, 2, 3, 4]));
+        assert!(!Dampener::PROBLEM_DAMPENER.is_safe(&[5, 1, 2, 9, 4]));
+    }
+
+    #[test]
+    fn test_classify() {
+        let dampener = Dampener::PROBLEM_DAMPENER;
+        assert_eq!(
+            dampener.classify(&[7, 6, 4, 2, 1]),
+            ReportSafety::Safe(Direction::Descending)
+        );
+        assert_eq!(
+            dampener.classify(&[1, 3, 2, 4, 5]),
+            ReportSafety::Dampened {
+                direction: Direction::Ascending,
+                removed: vec![1]
+            }
+        );
+        assert_eq!(
+            dampener.classify(&[1, 2, 7, 8, 9]),
+            ReportSafety::Unsafe(UnsafeStep {
+                index: 2,
+                from: 2,
+                to: 7,
+                reason: UnsafeReason::StepTooLarge
+            })
+        );
+        assert_eq!(
+            Dampener::STRICT.classify(&[8, 6, 4, 4, 1]),
+            ReportSafety::Unsafe(UnsafeStep {
+                index: 3,
+                from: 4,
+                to: 4,
+                reason: UnsafeReason::Flat
+            })
+        );
+        assert_eq!(
+            Dampener::STRICT.classify(&[1, 3, 2, 4, 5]),
+            ReportSafety::Unsafe(UnsafeStep {
+                index: 2,
+                from: 3,
+                to: 2,
+                reason: UnsafeReason::DirectionChange
+            })
+        );
+    }
+
+    #[test]
+    fn test_explain() {
+        let input = "7 6 4 2 1
+1 2 7 8 9
+1 3 2 4 5
+8 6 4 4 1";
+        assert_eq!(
+            explain(input, Dampener::PROBLEM_DAMPENER),
+            "7 6 4 2 1: safe, descending
+1 2 7 8 9: unsafe, step too large from 2 at index 1 to 7 at index 2
+1 3 2 4 5: safe with dampener, ascending after removing index 1
+8 6 4 4 1: safe with dampener, descending after removing index 2"
+        );
+    }
+
     #[test]
     fn test_part1() {
         let input = "7 6 4 2 1
@@ -1,172 +1,539 @@
 use crate::solution::Solution;
-use nom::character::complete::{anychar, digit1};
+use nom::bytes::complete::take_while_m_n;
 use nom::combinator::map_res;
-use nom::multi::{many1, many_till};
 use nom::sequence::{delimited, separated_pair};
 use nom::{IResult, Parser};
 use nom_supreme::error::ErrorTree;
 use nom_supreme::tag::complete::tag;
 use nom_supreme::ParserExt;
+use std::fmt::Debug;
+use std::io::{stdout, IsTerminal};
+use std::ops::Range;
 
 pub struct AdventPuzzle;
 
 impl Solution for AdventPuzzle {
     fn part1(input: &str) -> String {
-        input
-            .lines()
-            .map(|line| run_line_basic(line))
-            .sum::<usize>()
-            .to_string()
+        Interpreter::basic().run(input).total.to_string()
     }
 
     fn part2(input: &str) -> String {
-        let instructions: Vec<_> = input
-            .lines()
-            .map(|line| parse_line_do_dont(line).expect("Could not parse line"))
-            .map(|(_, instructions)| instructions)
-            .collect();
+        Interpreter::with_conditionals()
+            .run(input)
+            .total
+            .to_string()
+    }
 
-        let combined_instructions = Instructions::combine(&instructions);
-        combined_instructions.process().to_string()
+    fn explain_part1(input: &str) -> Option<String> {
+        Some(render_trace(
+            input,
+            &Interpreter::basic().trace(input),
+            stdout().is_terminal(),
+        ))
     }
+
+    fn explain_part2(input: &str) -> Option<String> {
+        Some(render_trace(
+            input,
+            &Interpreter::with_conditionals().trace(input),
+            stdout().is_terminal(),
+        ))
+    }
+}
+
+const COUNTED: &str = "\x1b[1;32m";
+const SKIPPED: &str = "\x1b[1;31m";
+const SWITCH: &str = "\x1b[1;33m";
+const RESET: &str = "\x1b[0m";
+
+/// An instruction found in the corrupted memory and where it was found
+#[derive(Debug)]
+struct Token {
+    operation: Box<dyn Operation>,
+    span: Range<usize>,
 }
 
 #[derive(Copy, Clone, PartialEq, Debug)]
-enum Instruction {
-    Multiply(usize, usize),
-    Do,
-    Dont,
-}
-
-#[derive(PartialEq, Debug)]
-struct Instructions(Vec<Instruction>);
-
-impl Instructions {
-    fn process(&self) -> usize {
-        let mut do_multiply = true;
-        let mut total = 0;
-        for instruction in &self.0 {
-            match instruction {
-                Instruction::Multiply(a, b) => {
-                    if do_multiply {
-                        total += a * b;
-                    }
+struct Machine {
+    enabled: bool,
+    total: usize,
+}
+
+impl Default for Machine {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            total: 0,
+        }
+    }
+}
+
+/// How an instruction shows up in the trace
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Effect {
+    Counted,
+    Skipped,
+    Switch,
+}
+
+/// A parsed instruction, carrying whatever it needs to run
+trait Operation: Debug {
+    fn execute(&self, machine: &mut Machine);
+
+    /// What running it did, given the machine afterwards
+    fn effect(&self, machine: &Machine) -> Effect;
+}
+
+/// Recognises one kind of instruction in the memory, the scanner knows nothing more about it
+trait InstructionHandler {
+    fn parse<'a>(&self, input: &'a str)
+        -> IResult<&'a str, Box<dyn Operation>, ErrorTree<&'a str>>;
+}
+
+/// Any parser producing an operation is a handler, so a new instruction is just a parser
+impl<F> InstructionHandler for F
+where
+    F: Fn(&str) -> IResult<&str, Box<dyn Operation>, ErrorTree<&str>>,
+{
+    fn parse<'a>(
+        &self,
+        input: &'a str,
+    ) -> IResult<&'a str, Box<dyn Operation>, ErrorTree<&'a str>> {
+        self(input)
+    }
+}
+
+#[derive(Debug)]
+struct Multiply(usize, usize);
+
+impl Operation for Multiply {
+    fn execute(&self, machine: &mut Machine) {
+        if machine.enabled {
+            machine.total += self.0 * self.1;
+        }
+    }
+
+    fn effect(&self, machine: &Machine) -> Effect {
+        if machine.enabled {
+            Effect::Counted
+        } else {
+            Effect::Skipped
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Do;
+
+impl Operation for Do {
+    fn execute(&self, machine: &mut Machine) {
+        machine.enabled = true;
+    }
+
+    fn effect(&self, _machine: &Machine) -> Effect {
+        Effect::Switch
+    }
+}
+
+#[derive(Debug)]
+struct Dont;
+
+impl Operation for Dont {
+    fn execute(&self, machine: &mut Machine) {
+        machine.enabled = false;
+    }
+
+    fn effect(&self, _machine: &Machine) -> Effect {
+        Effect::Switch
+    }
+}
+
+/// Walks through the whole input once, asking each handler in turn whether an instruction starts
+/// at the current position. Anything not recognised is skipped a character at a time.
+struct Scanner<'a> {
+    input: &'a str,
+    position: usize,
+    handlers: &'a [Box<dyn InstructionHandler>],
+}
+
+impl Iterator for Scanner<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(next_char) = self.input[self.position..].chars().next() {
+            let rest = &self.input[self.position..];
+            let found = self
+                .handlers
+                .iter()
+                .find_map(|handler| handler.parse(rest).ok());
+
+            match found {
+                Some((remainder, operation)) => {
+                    let start = self.position;
+                    self.position += rest.len() - remainder.len();
+                    return Some(Token {
+                        operation,
+                        span: start..self.position,
+                    });
                 }
-                Instruction::Do => do_multiply = true,
-                Instruction::Dont => do_multiply = false,
+                None => self.position += next_char.len_utf8(),
             }
         }
-        total
+        None
     }
+}
 
-    fn combine(instructions: &[Instructions]) -> Instructions {
-        let new_instructions = instructions
-            .into_iter()
-            .flat_map(|i| i.0.iter().copied())
-            .collect::<Vec<_>>();
-        Instructions(new_instructions)
+struct Interpreter {
+    handlers: Vec<Box<dyn InstructionHandler>>,
+}
+
+impl Interpreter {
+    fn basic() -> Self {
+        Self {
+            handlers: vec![Box::new(parse_mul)],
+        }
+    }
+
+    fn with_conditionals() -> Self {
+        Self {
+            handlers: vec![
+                Box::new(parse_mul),
+                Box::new(parse_do),
+                Box::new(parse_dont),
+            ],
+        }
+    }
+
+    fn scan<'a>(&'a self, input: &'a str) -> Scanner<'a> {
+        Scanner {
+            input,
+            position: 0,
+            handlers: &self.handlers,
+        }
+    }
+
+    /// Runs the input, recording the state of the machine after each instruction
+    fn trace(&self, input: &str) -> Vec<TraceStep> {
+        let mut machine = Machine::default();
+        self.scan(input)
+            .map(|token| {
+                token.operation.execute(&mut machine);
+                TraceStep { token, machine }
+            })
+            .collect()
+    }
+
+    fn run(&self, input: &str) -> Machine {
+        let mut machine = Machine::default();
+        for token in self.scan(input) {
+            token.operation.execute(&mut machine);
+        }
+        machine
     }
 }
 
-fn parse_mul(input: &str) -> IResult<&str, Instruction, ErrorTree<&str>> {
-    delimited(
-        tag("("),
-        separated_pair(
-            map_res(digit1, str::parse),
-            tag(","),
-            map_res(digit1, str::parse),
-        ),
-        tag(")"),
-    )
-    .map(|(a, b)| Instruction::Multiply(a, b))
-    .preceded_by(tag("mul"))
-    .parse(input)
+/// Operands are 1 to 3 digits
+fn parse_operand(input: &str) -> IResult<&str, usize, ErrorTree<&str>> {
+    map_res(
+        take_while_m_n(1, 3, |c: char| c.is_ascii_digit()),
+        str::parse,
+    )(input)
 }
 
-fn parse_do(input: &str) -> IResult<&str, Instruction, ErrorTree<&str>> {
-    tag("do()").map(|_| Instruction::Do).parse(input)
+#[derive(Debug)]
+struct TraceStep {
+    token: Token,
+    machine: Machine,
 }
 
-fn parse_dont(input: &str) -> IResult<&str, Instruction, ErrorTree<&str>> {
-    tag("don't()").map(|_| Instruction::Dont).parse(input)
+/// Line and column (both from 1) of byte offsets into the input. Offsets have to be asked for in
+/// order, so each character is only counted once however many instructions there are.
+struct LineCounter<'a> {
+    input: &'a str,
+    position: usize,
+    line: usize,
+    column: usize,
 }
 
-fn parse_instruction(input: &str) -> IResult<&str, Instruction, ErrorTree<&str>> {
-    parse_mul.or(parse_do).or(parse_dont).parse(input)
+impl<'a> LineCounter<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            position: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn advance_to(&mut self, offset: usize) -> (usize, usize) {
+        for c in self.input[self.position..offset].chars() {
+            if c == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+        self.position = offset;
+        (self.line, self.column)
+    }
 }
 
-fn parse_line_basic(input: &str) -> IResult<&str, Instructions, ErrorTree<&str>> {
-    many1(many_till(anychar, parse_mul))
-        .parse(input)
-        .map(|(_, result)| {
-            let instructions = Instructions(result.into_iter().map(|(_s, m)| m).collect());
-            ("", instructions) // Dump the remainder
-        })
+/// Lists each instruction with where it was found and what it did. With `colour`, also repeats
+/// the input with counted multiplications in green, skipped ones in red, and switches in yellow.
+fn render_trace(input: &str, steps: &[TraceStep], colour: bool) -> String {
+    let mut trace = String::new();
+    let mut lines = LineCounter::new(input);
+    for TraceStep { token, machine } in steps {
+        let (line, column) = lines.advance_to(token.span.start);
+        let state = if machine.enabled {
+            "enabled"
+        } else {
+            "disabled"
+        };
+        trace.push_str(&format!(
+            "{:<9} {:<14} {state:<8} total {}\n",
+            format!("{line}:{column}"),
+            &input[token.span.clone()],
+            machine.total
+        ));
+    }
+    if !colour {
+        return trace;
+    }
+
+    trace.push('\n');
+    let mut position = 0;
+    for TraceStep { token, machine } in steps {
+        let colour = match token.operation.effect(machine) {
+            Effect::Counted => COUNTED,
+            Effect::Skipped => SKIPPED,
+            Effect::Switch => SWITCH,
+        };
+        trace.push_str(&input[position..token.span.start]);
+        trace.push_str(&format!("{colour}{}{RESET}", &input[token.span.clone()]));
+        position = token.span.end;
+    }
+    trace.push_str(&input[position..]);
+    trace
 }
 
-fn run_line_basic(input: &str) -> usize {
-    let (_, list) = parse_line_basic(input).expect("failed to parse line");
-    list.process()
+fn parse_mul(input: &str) -> IResult<&str, Box<dyn Operation>, ErrorTree<&str>> {
+    delimited(
+        tag("("),
+        separated_pair(parse_operand, tag(","), parse_operand),
+        tag(")"),
+    )
+    .map(|(a, b)| Box::new(Multiply(a, b)) as Box<dyn Operation>)
+    .preceded_by(tag("mul"))
+    .parse(input)
 }
 
-fn parse_line_do_dont(input: &str) -> IResult<&str, Instructions, ErrorTree<&str>> {
-    many1(many_till(anychar, parse_instruction))
+fn parse_do(input: &str) -> IResult<&str, Box<dyn Operation>, ErrorTree<&str>> {
+    tag("do()")
+        .map(|_| Box::new(Do) as Box<dyn Operation>)
         .parse(input)
-        .map(|(_, result)| {
-            let instructions = Instructions(result.into_iter().map(|(_s, m)| m).collect());
-            ("", instructions) // Dump the remainder
-        })
 }
 
-// fn run_line_do_dont(input: &str) -> usize {
-//     let (_, list) = parse_line_do_dont(input).expect("failed to parse line");
-//     list.process()
-// }
+fn parse_dont(input: &str) -> IResult<&str, Box<dyn Operation>, ErrorTree<&str>> {
+    tag("don't()")
+        .map(|_| Box::new(Dont) as Box<dyn Operation>)
+        .parse(input)
+}
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use nom::character::complete::digit1;
 
     #[test]
     fn test_parse_mul() {
-        let (_, pair) = parse_mul("mul(1,2)").expect("failed to parse mul");
-        assert_eq!(pair, Instruction::Multiply(1, 2));
-        assert!(parse_mul("mul(1, 2)").is_err())
+        let (_, multiply) = parse_mul("mul(1,2)").expect("failed to parse mul");
+        let mut machine = Machine::default();
+        multiply.execute(&mut machine);
+        assert_eq!(machine.total, 2);
+        assert!(parse_mul("mul(1, 2)").is_err());
+        assert!(parse_mul("mul(999,100)").is_ok());
+        assert!(parse_mul("mul(1234,5)").is_err());
+        assert!(parse_mul("mul(5,1234)").is_err());
+        assert!(parse_mul("mul(,5)").is_err());
+    }
+
+    #[test]
+    fn test_no_valid_instructions() {
+        assert_eq!(AdventPuzzle::part1(""), "0");
+        assert_eq!(AdventPuzzle::part1("mul[3,7]!@^do_not_mul(32,64]"), "0");
+        assert_eq!(AdventPuzzle::part2("don't(mul(1234,2)"), "0");
+
+        let input = "mul(2,4)
+!@^%&*
+mul(3,3)";
+        assert_eq!(AdventPuzzle::part1(input), "17");
+        assert_eq!(AdventPuzzle::part2(input), "17");
+    }
+
+    #[test]
+    fn test_instructions_across_line_breaks() {
+        // Instructions can't be split over a line break
+        let input = "mul(2,
+4)mul
+(3,3)don't(
+)mul(5,5)";
+        assert_eq!(AdventPuzzle::part1(input), "25");
+        assert_eq!(AdventPuzzle::part2(input), "25");
+
+        // But whether multiplication is enabled carries on to the next line
+        let input = "mul(2,4)don't()
+mul(3,3)do()
+mul(5,5)";
+        assert_eq!(AdventPuzzle::part1(input), "42");
+        assert_eq!(AdventPuzzle::part2(input), "33");
+    }
+
+    /// Each instruction found along with whether multiplication is enabled and the total after it
+    fn scan_steps<'a>(interpreter: &Interpreter, input: &'a str) -> Vec<(&'a str, bool, usize)> {
+        interpreter
+            .trace(input)
+            .into_iter()
+            .map(|TraceStep { token, machine }| {
+                (&input[token.span], machine.enabled, machine.total)
+            })
+            .collect()
     }
 
     #[test]
-    fn test_parse_line_basic() {
-        let (_, pairs) = parse_line_basic(
+    fn test_scan_basic() {
+        let steps = scan_steps(
+            &Interpreter::basic(),
             "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))",
-        )
-        .expect("failed to parse mul");
+        );
         assert_eq!(
-            pairs,
-            Instructions(vec![
-                Instruction::Multiply(2, 4),
-                Instruction::Multiply(5, 5),
-                Instruction::Multiply(11, 8),
-                Instruction::Multiply(8, 5)
-            ])
+            steps,
+            [
+                ("mul(2,4)", true, 8),
+                ("mul(5,5)", true, 33),
+                ("mul(11,8)", true, 121),
+                ("mul(8,5)", true, 161)
+            ]
         );
     }
 
     #[test]
-    fn test_parse_line_do_dont() {
-        let (_, pairs) = parse_line_do_dont(
+    fn test_scan_do_dont() {
+        let steps = scan_steps(
+            &Interpreter::with_conditionals(),
             "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))",
-        )
-        .expect("failed to parse mul");
+        );
         assert_eq!(
-            pairs,
-            Instructions(vec![
-                Instruction::Multiply(2, 4),
-                Instruction::Dont,
-                Instruction::Multiply(5, 5),
-                Instruction::Multiply(11, 8),
-                Instruction::Do,
-                Instruction::Multiply(8, 5)
-            ])
+            steps,
+            [
+                ("mul(2,4)", true, 8),
+                ("don't()", false, 8),
+                ("mul(5,5)", false, 8),
+                ("mul(11,8)", false, 8),
+                ("do()", true, 8),
+                ("mul(8,5)", true, 48)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_spans() {
+        let input = "xmul(2,4)é&don't()_mul(5,5)";
+        let spans: Vec<_> = Interpreter::with_conditionals()
+            .scan(input)
+            .map(|token| token.span)
+            .collect();
+        assert_eq!(spans, vec![1..9, 12..19, 20..28]);
+        assert_eq!(&input[12..19], "don't()");
+    }
+
+    #[test]
+    fn test_new_handler() {
+        // A new instruction, defined entirely outside the scanner and interpreter, that adds
+        // its operands when multiplications are enabled
+        #[derive(Debug)]
+        struct Add(usize, usize);
+
+        impl Operation for Add {
+            fn execute(&self, machine: &mut Machine) {
+                if machine.enabled {
+                    machine.total += self.0 + self.1;
+                }
+            }
+
+            fn effect(&self, machine: &Machine) -> Effect {
+                Multiply(0, 0).effect(machine)
+            }
+        }
+
+        fn parse_add(input: &str) -> IResult<&str, Box<dyn Operation>, ErrorTree<&str>> {
+            delimited(
+                tag("add("),
+                separated_pair(
+                    map_res(digit1, str::parse),
+                    tag(","),
+                    map_res(digit1, str::parse),
+                ),
+                tag(")"),
+            )
+            .map(|(a, b)| Box::new(Add(a, b)) as Box<dyn Operation>)
+            .parse(input)
+        }
+
+        let mut interpreter = Interpreter::with_conditionals();
+        interpreter.handlers.push(Box::new(parse_add));
+        let input = "mul(2,4)add(10,20)don't()add(1,1)do()add(3,4)";
+        assert_eq!(
+            scan_steps(&interpreter, input),
+            [
+                ("mul(2,4)", true, 8),
+                ("add(10,20)", true, 38),
+                ("don't()", false, 38),
+                ("add(1,1)", false, 38),
+                ("do()", true, 38),
+                ("add(3,4)", true, 45)
+            ]
+        );
+        assert_eq!(interpreter.run(input).total, 8 + 30 + 7);
+    }
+
+    #[test]
+    fn test_trace() {
+        let input = "xmul(2,4)&don't()
+_mul(5,5)do()?";
+        let interpreter = Interpreter::with_conditionals();
+        let steps = interpreter.trace(input);
+        assert_eq!(
+            steps
+                .iter()
+                .map(|step| (step.machine.enabled, step.machine.total))
+                .collect::<Vec<_>>(),
+            vec![(true, 8), (false, 8), (false, 8), (true, 8)]
+        );
+        let mut lines = LineCounter::new(input);
+        assert_eq!(lines.advance_to(steps[1].token.span.start), (1, 11));
+        assert_eq!(lines.advance_to(steps[2].token.span.start), (2, 2));
+
+        assert_eq!(
+            render_trace(input, &steps, false),
+            "1:2       mul(2,4)       enabled  total 8
+1:11      don't()        disabled total 8
+2:2       mul(5,5)       disabled total 8
+2:10      do()           enabled  total 8
+"
+        );
+        assert_eq!(
+            render_trace(input, &steps, true),
+            format!(
+                "1:2       mul(2,4)       enabled  total 8
+1:11      don't()        disabled total 8
+2:2       mul(5,5)       disabled total 8
+2:10      do()           enabled  total 8
+
+x{COUNTED}mul(2,4){RESET}&{SWITCH}don't(){RESET}
+_{SKIPPED}mul(5,5){RESET}{SWITCH}do(){RESET}?"
+            )
         );
     }
 
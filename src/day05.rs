@@ -7,6 +7,10 @@ use nom_supreme::error::ErrorTree;
 use nom_supreme::tag::complete::tag;
 use nom_supreme::ParserExt;
 use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
 use std::ops::Deref;
 
 pub struct AdventPuzzle;
@@ -28,16 +32,101 @@ impl Solution for AdventPuzzle {
             .0
             .iter_mut()
             .filter(|order| !order.is_valid(&page_rules))
-            .map(|order| {
-                order.fix(&page_rules);
-                order.get_middle_page()
+            .filter_map(|order| match order.fix(&page_rules) {
+                Ok(()) => Some(order.get_middle_page()),
+                Err(e) => {
+                    eprintln!("Warning: skipping {order:?}, {e}");
+                    None
+                }
             })
             .sum::<usize>()
             .to_string()
     }
+
+    fn explain_part1(input: &str) -> Option<String> {
+        let (_, (page_rules, page_orders)) = parse_page_rules_and_orders(input).unwrap();
+        let mut explanation = page_rules.describe_cycles();
+        for order in page_orders.iter() {
+            explanation.push_str(&format!("\n{order}: "));
+            let violations = order.violations(&page_rules);
+            if violations.is_empty() {
+                explanation.push_str(&format!("valid, middle page {}", order.get_middle_page()));
+            } else {
+                explanation.push_str("rejected");
+                for violation in violations {
+                    explanation.push_str(&format!("\n  {violation}"));
+                }
+            }
+        }
+        Some(explanation)
+    }
+
+    fn explain_part2(input: &str) -> Option<String> {
+        let (_, (page_rules, mut page_orders)) = parse_page_rules_and_orders(input).unwrap();
+        let mut explanation = page_rules.describe_cycles();
+        for order in page_orders.0.iter_mut() {
+            let violations = order.violations(&page_rules);
+            if violations.is_empty() {
+                continue;
+            }
+            explanation.push_str(&format!("\n{order}: rejected"));
+            for violation in violations {
+                explanation.push_str(&format!("\n  {violation}"));
+            }
+            match order.fix(&page_rules) {
+                Ok(()) => explanation.push_str(&format!(
+                    "\n  fixed to {order}, middle page {}",
+                    order.get_middle_page()
+                )),
+                Err(e) => explanation.push_str(&format!("\n  unfixable, {e}")),
+            }
+        }
+        Some(explanation)
+    }
 }
 
-#[derive(Debug, PartialEq)]
+/// Renders the rules as a Graphviz DOT digraph.
+///
+/// With an update number (counting from 1) only the rules between that update's pages are
+/// included, and the ones it breaks are drawn in red.
+pub fn render_dot(input: &str, update: Option<usize>) -> Result<String, DotError<'_>> {
+    let (_, (page_rules, page_orders)) =
+        parse_page_rules_and_orders(input).map_err(DotError::Parse)?;
+    match update {
+        None => Ok(page_rules.to_dot(None)),
+        Some(update) => {
+            let order = update
+                .checked_sub(1)
+                .and_then(|index| page_orders.get(index))
+                .ok_or(DotError::NoSuchUpdate {
+                    update,
+                    updates: page_orders.len(),
+                })?;
+            Ok(page_rules.subset(order).to_dot(Some(order)))
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum DotError<'a> {
+    Parse(nom::Err<ErrorTree<&'a str>>),
+    NoSuchUpdate { update: usize, updates: usize },
+}
+
+impl Display for DotError<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DotError::Parse(error) => write!(f, "could not parse rules and updates: {error}"),
+            DotError::NoSuchUpdate { update, updates } => {
+                write!(f, "no update {update}, updates are numbered 1 to {updates}")
+            }
+        }
+    }
+}
+
+impl Error for DotError<'_> {}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
 struct PageRule {
     before: usize,
     after: usize,
@@ -50,7 +139,7 @@ impl PageRule {
     }
 
     fn get_page_after(&self, page: usize) -> Option<usize> {
-        (page == self.before).then(|| self.after)
+        (page == self.before).then_some(self.after)
     }
 }
 
@@ -75,10 +164,126 @@ struct PageRules(Vec<PageRule>);
 impl PageRules {
     fn get_pages_after(&self, page: usize) -> Vec<usize> {
         self.iter()
-            .map(|rule| rule.get_page_after(page))
-            .filter_map(|rule| rule)
+            .filter_map(|rule| rule.get_page_after(page))
             .collect()
     }
+
+    /// Only the rules where both pages are in the given set
+    fn subset(&self, pages: &[usize]) -> PageRules {
+        PageRules(
+            self.iter()
+                .filter(|rule| pages.contains(&rule.before) && pages.contains(&rule.after))
+                .copied()
+                .collect(),
+        )
+    }
+
+    fn graph(&self) -> BTreeMap<usize, Vec<usize>> {
+        let mut graph: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for rule in self.iter() {
+            graph.entry(rule.before).or_default().push(rule.after);
+        }
+        graph
+    }
+
+    /// Finds the shortest chain of rules that leads from a page back to itself, if there is one.
+    ///
+    /// A breadth first search from each page finds the shortest cycle through that page, so the
+    /// shortest of those is the shortest in the whole rule set.
+    fn shortest_cycle(&self) -> Option<PageCycle> {
+        let graph = self.graph();
+        let mut shortest: Option<PageCycle> = None;
+
+        for &start in graph.keys() {
+            let mut parents: HashMap<usize, usize> = HashMap::new();
+            let mut queue = VecDeque::from([start]);
+
+            'search: while let Some(page) = queue.pop_front() {
+                for &next in graph.get(&page).into_iter().flatten() {
+                    if next == start {
+                        let mut cycle = vec![page];
+                        let mut current = page;
+                        while current != start {
+                            current = parents[&current];
+                            cycle.push(current);
+                        }
+                        cycle.reverse();
+
+                        if shortest.as_ref().is_none_or(|s| cycle.len() < s.len()) {
+                            shortest = Some(PageCycle(cycle));
+                        }
+                        break 'search;
+                    }
+                    if let Entry::Vacant(entry) = parents.entry(next) {
+                        entry.insert(page);
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        shortest
+    }
+
+    /// Graphviz DOT digraph of the rules, edges the order breaks are highlighted
+    fn to_dot(&self, order: Option<&PageOrder>) -> String {
+        let violated: Vec<&PageRule> = order
+            .map(|order| {
+                order
+                    .violations(self)
+                    .into_iter()
+                    .map(|violation| violation.rule)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut dot = String::from("digraph page_rules {\n");
+        for page in order.into_iter().flat_map(|order| order.iter()) {
+            dot.push_str(&format!("    {page};\n"));
+        }
+        for rule in self.iter() {
+            if violated.contains(&rule) {
+                dot.push_str(&format!(
+                    "    {} -> {} [color=red, penwidth=2];\n",
+                    rule.before, rule.after
+                ));
+            } else {
+                dot.push_str(&format!("    {} -> {};\n", rule.before, rule.after));
+            }
+        }
+        dot.push('}');
+        dot
+    }
+
+    fn describe_cycles(&self) -> String {
+        match self.shortest_cycle() {
+            Some(cycle) => format!(
+                "Rules contain a cycle so there is no global page order, shortest cycle: {cycle}"
+            ),
+            None => "Rules contain no cycles".to_string(),
+        }
+    }
+}
+
+/// Pages where each must come before the next, and the last must come before the first
+#[derive(Debug, PartialEq)]
+struct PageCycle(Vec<usize>);
+
+impl Deref for PageCycle {
+    type Target = Vec<usize>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Display for PageCycle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for page in self.iter() {
+            write!(f, "{page} -> ")?;
+        }
+        write!(f, "{}", self[0])
+    }
 }
 
 impl Deref for PageRules {
@@ -104,6 +309,13 @@ impl Deref for PageOrder {
     }
 }
 
+impl Display for PageOrder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let pages: Vec<_> = self.iter().map(usize::to_string).collect();
+        write!(f, "{}", pages.join(","))
+    }
+}
+
 impl PageOrder {
     fn get_middle_page(&self) -> usize {
         self[self.0.len() / 2]
@@ -123,7 +335,39 @@ impl PageOrder {
         true
     }
 
-    fn fix(&mut self, page_rules: &PageRules) {
+    /// Every rule this order breaks, in the order the offending pages appear
+    fn violations<'a>(&self, page_rules: &'a PageRules) -> Vec<RuleViolation<'a>> {
+        let indices: HashMap<usize, usize> = self
+            .iter()
+            .enumerate()
+            .map(|(index, page)| (*page, index))
+            .collect();
+
+        let mut violations: Vec<_> = page_rules
+            .iter()
+            .filter_map(|rule| {
+                let before_index = *indices.get(&rule.before)?;
+                let after_index = *indices.get(&rule.after)?;
+                (after_index < before_index).then_some(RuleViolation {
+                    rule,
+                    before_index,
+                    after_index,
+                })
+            })
+            .collect();
+        violations.sort_by_key(|violation| (violation.after_index, violation.before_index));
+        violations
+    }
+
+    fn fix(&mut self, page_rules: &PageRules) -> Result<(), PageOrderError> {
+        if let Some(cycle) = page_rules.subset(self).shortest_cycle() {
+            return Err(PageOrderError::Cyclic(cycle));
+        }
+        self.sort_by_rules(page_rules);
+        Ok(())
+    }
+
+    fn sort_by_rules(&mut self, page_rules: &PageRules) {
         for i in 0..self.0.len() {
             let current_page = self[i];
             let pages_after_current_page = page_rules.get_pages_after(current_page);
@@ -133,12 +377,52 @@ impl PageOrder {
                 .find(|(_j, previous_page)| pages_after_current_page.contains(previous_page))
             {
                 self.0.swap(i, j);
-                self.fix(&page_rules);
+                self.sort_by_rules(page_rules);
+            }
+        }
+    }
+}
+
+/// A rule broken by a page order, the page that should be after was found first
+#[derive(Debug, PartialEq)]
+struct RuleViolation<'a> {
+    rule: &'a PageRule,
+    before_index: usize,
+    after_index: usize,
+}
+
+impl Display for RuleViolation<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}|{} broken: {} at index {} comes before {} at index {}",
+            self.rule.before,
+            self.rule.after,
+            self.rule.after,
+            self.after_index,
+            self.rule.before,
+            self.before_index
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum PageOrderError {
+    Cyclic(PageCycle),
+}
+
+impl Display for PageOrderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PageOrderError::Cyclic(cycle) => {
+                write!(f, "rules for these pages form a cycle: {cycle}")
             }
         }
     }
 }
 
+impl Error for PageOrderError {}
+
 fn parse_page_order(input: &str) -> IResult<&str, PageOrder, ErrorTree<&str>> {
     separated_list1(tag(","), digit1.map_res(str::parse))(input)
         .map(|(remainder, order)| (remainder, PageOrder(order)))
@@ -264,6 +548,114 @@ mod test {
         assert!(!test_6.is_valid(&page_rules));
     }
 
+    #[test]
+    fn test_violations() {
+        let page_rules = parse_page_rules("47|53\n97|13\n97|61\n97|47\n75|29\n97|75\n29|13")
+            .unwrap()
+            .1;
+
+        let valid = PageOrder(vec![97, 75, 47, 29, 13]);
+        assert!(valid.violations(&page_rules).is_empty());
+
+        let invalid = PageOrder(vec![75, 13, 97, 29]);
+        assert_eq!(
+            invalid.violations(&page_rules),
+            vec![
+                RuleViolation {
+                    rule: &PageRule::new(97, 75),
+                    before_index: 2,
+                    after_index: 0,
+                },
+                RuleViolation {
+                    rule: &PageRule::new(97, 13),
+                    before_index: 2,
+                    after_index: 1,
+                },
+                RuleViolation {
+                    rule: &PageRule::new(29, 13),
+                    before_index: 3,
+                    after_index: 1,
+                },
+            ]
+        );
+        assert_eq!(
+            invalid.violations(&page_rules)[0].to_string(),
+            "97|75 broken: 75 at index 0 comes before 97 at index 2"
+        );
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let page_rules = parse_page_rules("1|2\n2|3\n3|4").unwrap().1;
+        assert_eq!(
+            page_rules.to_dot(None),
+            "digraph page_rules {
+    1 -> 2;
+    2 -> 3;
+    3 -> 4;
+}"
+        );
+
+        let order = PageOrder(vec![3, 1, 2]);
+        assert_eq!(
+            page_rules.subset(&order).to_dot(Some(&order)),
+            "digraph page_rules {
+    3;
+    1;
+    2;
+    1 -> 2;
+    2 -> 3 [color=red, penwidth=2];
+}"
+        );
+    }
+
+    #[test]
+    fn test_render_dot() {
+        let input = "1|2\n2|3\n\n1,2,3\n3,1\n";
+        assert!(render_dot(input, None).is_ok());
+        assert!(render_dot(input, Some(2)).is_ok());
+        assert!(matches!(
+            render_dot(input, Some(3)),
+            Err(DotError::NoSuchUpdate {
+                update: 3,
+                updates: 2
+            })
+        ));
+        assert!(matches!(
+            render_dot(input, Some(0)),
+            Err(DotError::NoSuchUpdate { update: 0, .. })
+        ));
+        assert!(matches!(render_dot("x", None), Err(DotError::Parse(_))));
+    }
+
+    #[test]
+    fn test_shortest_cycle() {
+        let acyclic = parse_page_rules("1|2\n2|3\n1|3").unwrap().1;
+        assert_eq!(acyclic.shortest_cycle(), None);
+
+        let cyclic = parse_page_rules("1|2\n2|3\n3|4\n4|1\n2|5\n5|3\n3|2")
+            .unwrap()
+            .1;
+        assert_eq!(cyclic.shortest_cycle(), Some(PageCycle(vec![2, 3])));
+        assert_eq!(cyclic.shortest_cycle().unwrap().to_string(), "2 -> 3 -> 2");
+    }
+
+    #[test]
+    fn test_fix_cyclic_order() {
+        let page_rules = parse_page_rules("1|2\n2|3\n3|1\n4|1").unwrap().1;
+
+        let mut unfixable = PageOrder(vec![3, 2, 1]);
+        assert_eq!(
+            unfixable.fix(&page_rules),
+            Err(PageOrderError::Cyclic(PageCycle(vec![1, 2, 3])))
+        );
+
+        // The cycle only matters if every page in it is part of the order
+        let mut fixable = PageOrder(vec![1, 4, 2]);
+        assert_eq!(fixable.fix(&page_rules), Ok(()));
+        assert_eq!(fixable, PageOrder(vec![4, 1, 2]));
+    }
+
     #[test]
     fn test_part1() {
         let input = "47|53
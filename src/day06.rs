@@ -211,13 +211,12 @@ impl Puzzle {
         self.map
             .iter()
             .enumerate()
-            .map(|(r, row)| {
+            .flat_map(|(r, row)| {
                 row.iter().enumerate().map(move |(c, _pos)| GuardPosition {
                     row: r as isize,
                     column: c as isize,
                 })
             })
-            .flatten()
             .collect()
     }
 
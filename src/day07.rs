@@ -70,7 +70,7 @@ impl SolutionTree {
         if let Some(branches) = &self.branches {
             return branches.add.could_equal(total) || branches.multiply.could_equal(total);
         }
-        return false;
+        false
     }
 }
 
@@ -120,7 +120,7 @@ impl SolutionTreePart2 {
                 || branches.multiply.could_equal(total)
                 || branches.concatenate.could_equal(total);
         }
-        return false;
+        false
     }
 }
 
@@ -161,6 +161,7 @@ mod test {
     use super::*;
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_can_brute_force() {
         let input = "190: 10 19";
         let equation = parse_equation(input).unwrap().1;
@@ -1,14 +1,177 @@
 use crate::solution::Solution;
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 pub struct AdventPuzzle;
 
 impl Solution for AdventPuzzle {
-    fn part1(_input: &str) -> String {
-        todo!()
+    fn part1(input: &str) -> String {
+        let map = AntennaMap::from_str(input).unwrap();
+        map.antinodes().len().to_string()
     }
 
-    fn part2(_input: &str) -> String {
-        todo!()
+    fn part2(input: &str) -> String {
+        let map = AntennaMap::from_str(input).unwrap();
+        map.harmonic_antinodes().len().to_string()
+    }
+
+    fn explain_part1(input: &str) -> Option<String> {
+        let map = AntennaMap::from_str(input).unwrap();
+        Some(map.render(&map.antinodes()))
+    }
+
+    fn explain_part2(input: &str) -> Option<String> {
+        let map = AntennaMap::from_str(input).unwrap();
+        Some(map.render(&map.harmonic_antinodes()))
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+struct Position {
+    row: isize,
+    column: isize,
+}
+
+fn gcd(a: isize, b: isize) -> isize {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct AntennaMap {
+    width: isize,
+    height: isize,
+    antennas: BTreeMap<char, Vec<Position>>,
+}
+
+impl AntennaMap {
+    fn is_in_bounds(&self, Position { row, column }: Position) -> bool {
+        row >= 0 && row < self.height && column >= 0 && column < self.width
+    }
+
+    /// Every pair of antennas that share a frequency
+    fn pairs(&self) -> impl Iterator<Item = (Position, Position)> + '_ {
+        self.antennas.values().flat_map(|positions| {
+            positions
+                .iter()
+                .enumerate()
+                .flat_map(move |(i, a)| positions[i + 1..].iter().map(move |b| (*a, *b)))
+        })
+    }
+
+    /// Points in line with a pair of antennas where one is twice as far away as the other
+    fn antinodes(&self) -> HashSet<Position> {
+        self.pairs()
+            .flat_map(|(a, b)| {
+                let row = b.row - a.row;
+                let column = b.column - a.column;
+                [
+                    Position {
+                        row: a.row - row,
+                        column: a.column - column,
+                    },
+                    Position {
+                        row: b.row + row,
+                        column: b.column + column,
+                    },
+                ]
+            })
+            .filter(|position| self.is_in_bounds(*position))
+            .collect()
+    }
+
+    /// Every grid point exactly in line with a pair of antennas, including the antennas.
+    ///
+    /// The step between antennas is reduced by its gcd so points between grid-aligned multiples
+    /// aren't missed.
+    fn harmonic_antinodes(&self) -> HashSet<Position> {
+        let mut antinodes = HashSet::new();
+        for (a, b) in self.pairs() {
+            let divisor = gcd(b.row - a.row, b.column - a.column);
+            let row = (b.row - a.row) / divisor;
+            let column = (b.column - a.column) / divisor;
+
+            for direction in [1, -1] {
+                let mut position = a;
+                while self.is_in_bounds(position) {
+                    antinodes.insert(position);
+                    position = Position {
+                        row: position.row + row * direction,
+                        column: position.column + column * direction,
+                    };
+                }
+            }
+        }
+        antinodes
+    }
+
+    /// The map with antinodes marked as `#`, antennas are shown over any antinode on them
+    fn render(&self, antinodes: &HashSet<Position>) -> String {
+        let mut grid = vec![vec!['.'; self.width as usize]; self.height as usize];
+        for position in antinodes {
+            grid[position.row as usize][position.column as usize] = '#';
+        }
+        for (frequency, positions) in &self.antennas {
+            for position in positions {
+                grid[position.row as usize][position.column as usize] = *frequency;
+            }
+        }
+        grid.into_iter()
+            .map(|row| row.into_iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum AntennaMapError {
+    Empty,
+    InvalidSize,
+}
+
+impl Display for AntennaMapError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AntennaMapError::Empty => write!(f, "empty map"),
+            AntennaMapError::InvalidSize => write!(f, "invalid size"),
+        }
+    }
+}
+
+impl Error for AntennaMapError {}
+
+impl FromStr for AntennaMap {
+    type Err = AntennaMapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = s.lines().filter(|line| !line.is_empty()).collect();
+        let width = lines.first().ok_or(AntennaMapError::Empty)?.chars().count();
+
+        let mut antennas: BTreeMap<char, Vec<Position>> = BTreeMap::new();
+        for (row, line) in lines.iter().enumerate() {
+            if line.chars().count() != width {
+                return Err(AntennaMapError::InvalidSize);
+            }
+            for (column, c) in line.chars().enumerate() {
+                if c != '.' {
+                    antennas.entry(c).or_default().push(Position {
+                        row: row as isize,
+                        column: column as isize,
+                    });
+                }
+            }
+        }
+
+        Ok(Self {
+            width: width as isize,
+            height: lines.len() as isize,
+            antennas,
+        })
     }
 }
 
@@ -16,17 +179,136 @@ impl Solution for AdventPuzzle {
 mod test {
     use super::*;
 
-    #[ignore]
+    #[test]
+    fn test_parse_antenna_map() {
+        let map = AntennaMap::from_str("..a.\n.A..\n...a").unwrap();
+        assert_eq!(map.width, 4);
+        assert_eq!(map.height, 3);
+        assert_eq!(
+            map.antennas,
+            BTreeMap::from([
+                ('A', vec![Position { row: 1, column: 1 }]),
+                (
+                    'a',
+                    vec![
+                        Position { row: 0, column: 2 },
+                        Position { row: 2, column: 3 }
+                    ]
+                ),
+            ])
+        );
+        assert_eq!(
+            AntennaMap::from_str("...\n.."),
+            Err(AntennaMapError::InvalidSize)
+        );
+    }
+
+    #[test]
+    fn test_render_antinodes() {
+        let input = "............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............";
+        let map = AntennaMap::from_str(input).unwrap();
+        assert_eq!(
+            map.render(&map.antinodes()),
+            "......#....#
+...#....0...
+....#0....#.
+..#....0....
+....0....#..
+.#....A.....
+...#........
+#......#....
+........A...
+.........A..
+..........#.
+..........#."
+        );
+    }
+
+    #[test]
+    fn test_harmonic_antinodes() {
+        let input = "T.........
+...T......
+.T........
+..........
+..........
+..........
+..........
+..........
+..........
+..........";
+        let map = AntennaMap::from_str(input).unwrap();
+        assert_eq!(map.harmonic_antinodes().len(), 9);
+        assert_eq!(
+            map.render(&map.harmonic_antinodes()),
+            "T....#....
+...T......
+.T....#...
+.........#
+..#.......
+..........
+...#......
+..........
+....#.....
+.........."
+        );
+    }
+
+    #[test]
+    fn test_harmonics_reduce_step() {
+        // Antennas 2 rows and 4 columns apart also line up with the point halfway between them
+        let map = AntennaMap::from_str("a....\n.....\n....a").unwrap();
+        assert_eq!(
+            map.harmonic_antinodes(),
+            HashSet::from([
+                Position { row: 0, column: 0 },
+                Position { row: 1, column: 2 },
+                Position { row: 2, column: 4 },
+            ])
+        );
+    }
+
     #[test]
     fn test_part1() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part1(input), "");
+        let input = "............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............";
+        assert_eq!(AdventPuzzle::part1(input), "14");
     }
 
-    #[ignore]
     #[test]
     fn test_part2() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part2(input), "");
+        let input = "............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............";
+        assert_eq!(AdventPuzzle::part2(input), "34");
     }
 }
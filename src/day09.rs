@@ -1,14 +1,189 @@
 use crate::solution::Solution;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::str::FromStr;
 
 pub struct AdventPuzzle;
 
 impl Solution for AdventPuzzle {
-    fn part1(_input: &str) -> String {
-        todo!()
+    fn part1(input: &str) -> String {
+        let disk_map = DiskMap::from_str(input).unwrap();
+        let mut blocks = disk_map.blocks();
+        compact_blocks(&mut blocks);
+        checksum(&blocks).to_string()
     }
 
-    fn part2(_input: &str) -> String {
-        todo!()
+    fn part2(input: &str) -> String {
+        let mut disk_map = DiskMap::from_str(input).unwrap();
+        disk_map.compact_files();
+        checksum(&disk_map.blocks()).to_string()
+    }
+
+    fn explain_part1(input: &str) -> Option<String> {
+        let disk_map = DiskMap::from_str(input).unwrap();
+        let before = disk_map.blocks();
+        let mut after = before.clone();
+        compact_blocks(&mut after);
+        Some(explain(&before, &after))
+    }
+
+    fn explain_part2(input: &str) -> Option<String> {
+        let mut disk_map = DiskMap::from_str(input).unwrap();
+        let before = disk_map.blocks();
+        disk_map.compact_files();
+        Some(explain(&before, &disk_map.blocks()))
+    }
+}
+
+/// Only files numbered 0 to 9 can be drawn with a single character each
+const MAX_RENDERED_FILES: usize = 10;
+
+fn explain(before: &[Option<usize>], after: &[Option<usize>]) -> String {
+    match (render(before), render(after)) {
+        (Some(before), Some(after)) => format!("{before}\n{after}"),
+        _ => format!("Too many files to draw, only up to {MAX_RENDERED_FILES} can be shown"),
+    }
+}
+
+/// Draws each block as its file id or `.` for free space, eg `00...111...2...`
+fn render(blocks: &[Option<usize>]) -> Option<String> {
+    blocks
+        .iter()
+        .map(|block| match block {
+            Some(id) if *id < MAX_RENDERED_FILES => char::from_digit(*id as u32, 10),
+            Some(_) => None,
+            None => Some('.'),
+        })
+        .collect()
+}
+
+fn checksum(blocks: &[Option<usize>]) -> usize {
+    blocks
+        .iter()
+        .enumerate()
+        .filter_map(|(position, block)| block.map(|id| position * id))
+        .sum()
+}
+
+/// Moves blocks one at a time from the end of the disk into the leftmost free space
+fn compact_blocks(blocks: &mut [Option<usize>]) {
+    let mut free = 0;
+    let mut end = blocks.len();
+    loop {
+        while free < end && blocks[free].is_some() {
+            free += 1;
+        }
+        while end > free && blocks[end - 1].is_none() {
+            end -= 1;
+        }
+        if end <= free + 1 {
+            break;
+        }
+        blocks.swap(free, end - 1);
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct Span {
+    start: usize,
+    length: usize,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct DiskMap {
+    /// Indexed by file id
+    files: Vec<Span>,
+    gaps: Vec<Span>,
+}
+
+impl DiskMap {
+    fn blocks(&self) -> Vec<Option<usize>> {
+        let size = self
+            .files
+            .iter()
+            .chain(&self.gaps)
+            .map(|span| span.start + span.length)
+            .max()
+            .unwrap_or_default();
+
+        let mut blocks = vec![None; size];
+        for (id, file) in self.files.iter().enumerate() {
+            blocks[file.start..file.start + file.length].fill(Some(id));
+        }
+        blocks
+    }
+
+    /// Moves whole files, highest id first, into the leftmost gap big enough to hold them.
+    ///
+    /// Gaps are at most 9 blocks long, so there's a min-heap of gap starts for each length. The
+    /// leftmost gap that fits is the smallest top of the heaps for lengths at least the file's.
+    /// Whatever is left of the gap goes back in the heap for its new length. Space freed by a move
+    /// is always to the right of the files still to move so no file could use it.
+    fn compact_files(&mut self) {
+        let mut freed = Vec::new();
+        let mut gaps: Vec<BinaryHeap<Reverse<usize>>> = vec![BinaryHeap::new(); 10];
+        for gap in &self.gaps {
+            gaps[gap.length].push(Reverse(gap.start));
+        }
+
+        for file in self.files.iter_mut().rev() {
+            if file.length == 0 {
+                continue;
+            }
+
+            let Some((length, start)) = (file.length..gaps.len())
+                .filter_map(|length| gaps[length].peek().map(|Reverse(start)| (length, *start)))
+                .min_by_key(|(_, start)| *start)
+            else {
+                continue;
+            };
+            if start > file.start {
+                continue;
+            }
+
+            gaps[length].pop();
+            gaps[length - file.length].push(Reverse(start + file.length));
+            freed.push(*file);
+            file.start = start;
+        }
+
+        self.gaps = gaps
+            .iter()
+            .enumerate()
+            .skip(1)
+            .flat_map(|(length, starts)| {
+                starts.iter().map(move |Reverse(start)| Span {
+                    start: *start,
+                    length,
+                })
+            })
+            .chain(freed)
+            .collect();
+        self.gaps.sort_by_key(|gap| gap.start);
+    }
+}
+
+impl FromStr for DiskMap {
+    type Err = char;
+
+    /// Digits alternate between file and gap lengths
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut files = Vec::new();
+        let mut gaps = Vec::new();
+        let mut start = 0;
+
+        for (i, c) in s.trim_end().chars().enumerate() {
+            let length = c.to_digit(10).ok_or(c)? as usize;
+            let span = Span { start, length };
+            if i % 2 == 0 {
+                files.push(span);
+            } else {
+                gaps.push(span);
+            }
+            start += length;
+        }
+
+        Ok(Self { files, gaps })
     }
 }
 
@@ -16,17 +191,86 @@ impl Solution for AdventPuzzle {
 mod test {
     use super::*;
 
-    #[ignore]
+    #[test]
+    fn test_parse_disk_map() {
+        let disk_map = DiskMap::from_str("12345\n").unwrap();
+        assert_eq!(
+            disk_map,
+            DiskMap {
+                files: vec![
+                    Span {
+                        start: 0,
+                        length: 1
+                    },
+                    Span {
+                        start: 3,
+                        length: 3
+                    },
+                    Span {
+                        start: 10,
+                        length: 5
+                    },
+                ],
+                gaps: vec![
+                    Span {
+                        start: 1,
+                        length: 2
+                    },
+                    Span {
+                        start: 6,
+                        length: 4
+                    },
+                ],
+            }
+        );
+        assert_eq!(DiskMap::from_str("12a45"), Err('a'));
+    }
+
+    #[test]
+    fn test_render() {
+        let disk_map = DiskMap::from_str("12345").unwrap();
+        assert_eq!(
+            render(&disk_map.blocks()),
+            Some("0..111....22222".to_string())
+        );
+
+        let many_files = DiskMap::from_str(&"1".repeat(21)).unwrap();
+        assert_eq!(render(&many_files.blocks()), None);
+    }
+
+    #[test]
+    fn test_compact_blocks() {
+        let mut blocks = DiskMap::from_str("12345").unwrap().blocks();
+        compact_blocks(&mut blocks);
+        assert_eq!(render(&blocks), Some("022111222......".to_string()));
+
+        let mut blocks = DiskMap::from_str("2333133121414131402").unwrap().blocks();
+        compact_blocks(&mut blocks);
+        assert_eq!(
+            render(&blocks),
+            Some("0099811188827773336446555566..............".to_string())
+        );
+    }
+
+    #[test]
+    fn test_compact_files() {
+        let mut disk_map = DiskMap::from_str("2333133121414131402").unwrap();
+        disk_map.compact_files();
+        assert_eq!(
+            render(&disk_map.blocks()),
+            Some("00992111777.44.333....5555.6666.....8888..".to_string())
+        );
+    }
+
     #[test]
     fn test_part1() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part1(input), "");
+        let input = "2333133121414131402";
+        assert_eq!(AdventPuzzle::part1(input), "1928");
     }
 
-    #[ignore]
     #[test]
     fn test_part2() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part2(input), "");
+        let input = "2333133121414131402";
+        assert_eq!(AdventPuzzle::part2(input), "2858");
     }
 }
@@ -1,14 +1,185 @@
 use crate::solution::Solution;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 pub struct AdventPuzzle;
 
 impl Solution for AdventPuzzle {
-    fn part1(_input: &str) -> String {
-        todo!()
+    fn part1(input: &str) -> String {
+        let map = TopographicMap::from_str(input).unwrap();
+        let summits = map.reachable_summits();
+        map.trailheads()
+            .map(|trailhead| summits[&trailhead].len())
+            .sum::<usize>()
+            .to_string()
     }
 
-    fn part2(_input: &str) -> String {
-        todo!()
+    fn part2(input: &str) -> String {
+        let map = TopographicMap::from_str(input).unwrap();
+        let ratings = map.ratings();
+        map.trailheads()
+            .map(|trailhead| ratings[&trailhead])
+            .sum::<usize>()
+            .to_string()
+    }
+
+    fn explain_part1(input: &str) -> Option<String> {
+        let map = TopographicMap::from_str(input).unwrap();
+        let summits = map.reachable_summits();
+        let explanation = map
+            .trailheads()
+            .map(|trailhead| format!("{trailhead}: score {}", summits[&trailhead].len()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Some(explanation)
+    }
+
+    fn explain_part2(input: &str) -> Option<String> {
+        let map = TopographicMap::from_str(input).unwrap();
+        let mut explanation = Vec::new();
+        for trailhead in map.trailheads() {
+            let trails = map.trails_from(trailhead);
+            explanation.push(format!("{trailhead}: rating {}", trails.len()));
+            for trail in trails {
+                let trail: Vec<_> = trail.iter().map(Position::to_string).collect();
+                explanation.push(format!("  {}", trail.join(" -> ")));
+            }
+        }
+        Some(explanation.join("\n"))
+    }
+}
+
+const SUMMIT: u8 = 9;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+struct Position {
+    row: usize,
+    column: usize,
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.row, self.column)
+    }
+}
+
+/// Heights of each point on the map, `None` for impassable points
+#[derive(Debug, PartialEq)]
+struct TopographicMap(Vec<Vec<Option<u8>>>);
+
+impl TopographicMap {
+    fn height(&self, Position { row, column }: Position) -> Option<u8> {
+        *self.0.get(row)?.get(column)?
+    }
+
+    fn positions(&self) -> impl Iterator<Item = Position> + '_ {
+        self.0.iter().enumerate().flat_map(|(row, heights)| {
+            (0..heights.len()).map(move |column| Position { row, column })
+        })
+    }
+
+    fn trailheads(&self) -> impl Iterator<Item = Position> + '_ {
+        self.positions()
+            .filter(|position| self.height(*position) == Some(0))
+    }
+
+    /// Neighbouring positions exactly one higher than this one
+    fn uphill(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        let Position { row, column } = position;
+        let next_height = self.height(position).map(|height| height + 1);
+        [
+            row.checked_sub(1).map(|row| Position { row, column }),
+            Some(Position {
+                row: row + 1,
+                column,
+            }),
+            column.checked_sub(1).map(|column| Position { row, column }),
+            Some(Position {
+                row,
+                column: column + 1,
+            }),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(move |neighbour| next_height.is_some() && self.height(*neighbour) == next_height)
+    }
+
+    /// Positions grouped by height, highest first, so anything uphill of a position has always
+    /// been visited before it
+    fn by_descending_height(&self) -> Vec<Position> {
+        let mut positions: Vec<_> = self
+            .positions()
+            .filter(|position| self.height(*position).is_some())
+            .collect();
+        positions.sort_by_key(|position| Reverse(self.height(*position)));
+        positions
+    }
+
+    /// For every position, the summits that can be reached from it
+    fn reachable_summits(&self) -> HashMap<Position, HashSet<Position>> {
+        let mut summits: HashMap<Position, HashSet<Position>> = HashMap::new();
+        for position in self.by_descending_height() {
+            let reachable = if self.height(position) == Some(SUMMIT) {
+                HashSet::from([position])
+            } else {
+                self.uphill(position)
+                    .flat_map(|neighbour| summits[&neighbour].iter().copied())
+                    .collect()
+            };
+            summits.insert(position, reachable);
+        }
+        summits
+    }
+
+    /// For every position, how many distinct trails lead from it to a summit
+    fn ratings(&self) -> HashMap<Position, usize> {
+        let mut ratings: HashMap<Position, usize> = HashMap::new();
+        for position in self.by_descending_height() {
+            let rating = if self.height(position) == Some(SUMMIT) {
+                1
+            } else {
+                self.uphill(position)
+                    .map(|neighbour| ratings[&neighbour])
+                    .sum()
+            };
+            ratings.insert(position, rating);
+        }
+        ratings
+    }
+
+    /// Every trail from the given position up to a summit
+    fn trails_from(&self, start: Position) -> Vec<Vec<Position>> {
+        if self.height(start) == Some(SUMMIT) {
+            return vec![vec![start]];
+        }
+        self.uphill(start)
+            .flat_map(|neighbour| self.trails_from(neighbour))
+            .map(|mut trail| {
+                trail.insert(0, start);
+                trail
+            })
+            .collect()
+    }
+}
+
+impl FromStr for TopographicMap {
+    type Err = char;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                line.chars()
+                    .map(|c| match c {
+                        '.' => Ok(None),
+                        c => c.to_digit(10).map(|height| Some(height as u8)).ok_or(c),
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
     }
 }
 
@@ -16,17 +187,101 @@ impl Solution for AdventPuzzle {
 mod test {
     use super::*;
 
-    #[ignore]
+    #[test]
+    fn test_parse_topographic_map() {
+        let map = TopographicMap::from_str("0123\n.98.").unwrap();
+        assert_eq!(
+            map,
+            TopographicMap(vec![
+                vec![Some(0), Some(1), Some(2), Some(3)],
+                vec![None, Some(9), Some(8), None],
+            ])
+        );
+        assert_eq!(TopographicMap::from_str("01x"), Err('x'));
+    }
+
+    #[test]
+    fn test_score_with_impassable() {
+        let input = "...0...
+...1...
+...2...
+6543456
+7.....7
+8.....8
+9.....9";
+        assert_eq!(AdventPuzzle::part1(input), "2");
+
+        let input = "10..9..
+2...8..
+3...7..
+4567654
+...8..3
+...9..2
+.....01";
+        assert_eq!(AdventPuzzle::part1(input), "3");
+    }
+
+    #[test]
+    fn test_rating_with_impassable() {
+        let input = ".....0.
+..4321.
+..5..2.
+..6543.
+..7..4.
+..8765.
+..9....";
+        assert_eq!(AdventPuzzle::part2(input), "3");
+    }
+
+    #[test]
+    fn test_trails_from() {
+        let input = ".....0.
+..4321.
+..5..2.
+..6543.
+..7..4.
+..8765.
+..9....";
+        let map = TopographicMap::from_str(input).unwrap();
+        let trails = map.trails_from(Position { row: 0, column: 5 });
+        assert_eq!(trails.len(), 3);
+        assert!(trails.iter().all(
+            |trail| trail.len() == 10 && trail.last() == Some(&Position { row: 6, column: 2 })
+        ));
+        assert_eq!(
+            trails[0][..4],
+            [
+                Position { row: 0, column: 5 },
+                Position { row: 1, column: 5 },
+                Position { row: 2, column: 5 },
+                Position { row: 3, column: 5 },
+            ]
+        );
+    }
+
     #[test]
     fn test_part1() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part1(input), "");
+        let input = "89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732";
+        assert_eq!(AdventPuzzle::part1(input), "36");
     }
 
-    #[ignore]
     #[test]
     fn test_part2() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part2(input), "");
+        let input = "89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732";
+        assert_eq!(AdventPuzzle::part2(input), "81");
     }
 }
@@ -1,32 +1,188 @@
 use crate::solution::Solution;
+use nom::character::complete::{digit1, line_ending, space1};
+use nom::multi::separated_list1;
+use nom::IResult;
+use nom_supreme::error::ErrorTree;
+use nom_supreme::final_parser::final_parser;
+use nom_supreme::ParserExt;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
 
 pub struct AdventPuzzle;
 
 impl Solution for AdventPuzzle {
-    fn part1(_input: &str) -> String {
-        todo!()
+    fn part1(input: &str) -> String {
+        count_stones(input, 25)
+            .expect("Could not count stones")
+            .to_string()
     }
 
-    fn part2(_input: &str) -> String {
-        todo!()
+    fn part2(input: &str) -> String {
+        count_stones(input, 75)
+            .expect("Could not count stones")
+            .to_string()
     }
 }
 
+/// Number of stones after blinking the given number of times
+pub fn count_stones(input: &str, blinks: usize) -> Result<u128, BlinkError<'_>> {
+    let mut stones = Stones::new(&get_stones(input).map_err(BlinkError::Parse)?);
+    for blink in 1..=blinks {
+        stones = stones.blink().ok_or(BlinkError::Overflow { blink })?;
+    }
+    stones.len().ok_or(BlinkError::CountOverflow { blinks })
+}
+
+/// Either the stones couldn't be read, or blinking long enough outgrew the integers we count with
+#[derive(Debug)]
+pub enum BlinkError<'a> {
+    Parse(ErrorTree<&'a str>),
+    Overflow { blink: usize },
+    CountOverflow { blinks: usize },
+}
+
+impl Display for BlinkError<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlinkError::Parse(error) => write!(f, "could not parse stones: {error}"),
+            BlinkError::Overflow { blink } => {
+                write!(f, "blink {blink}: a stone's number or count got too large")
+            }
+            BlinkError::CountOverflow { blinks } => {
+                write!(f, "too many stones to count after {blinks} blinks")
+            }
+        }
+    }
+}
+
+impl Error for BlinkError<'_> {}
+
+/// Stones change the same way regardless of where they are in the line, so only how many there
+/// are of each number matters
+#[derive(Debug, PartialEq)]
+struct Stones(HashMap<u64, u128>);
+
+impl Stones {
+    fn new(numbers: &[u64]) -> Self {
+        let mut stones = HashMap::new();
+        for number in numbers {
+            *stones.entry(*number).or_default() += 1;
+        }
+        Self(stones)
+    }
+
+    /// Total number of stones, if it fits
+    fn len(&self) -> Option<u128> {
+        self.0
+            .values()
+            .try_fold(0u128, |total, count| total.checked_add(*count))
+    }
+
+    /// The stones after one blink, or `None` if a number or count overflows
+    fn blink(&self) -> Option<Self> {
+        let mut stones: HashMap<u64, u128> = HashMap::with_capacity(self.0.len());
+        for (number, count) in &self.0 {
+            for new_number in blink_stone(*number)? {
+                let total = stones.entry(new_number).or_default();
+                *total = total.checked_add(*count)?;
+            }
+        }
+        Some(Self(stones))
+    }
+}
+
+/// What a single stone becomes after one blink, or `None` if its number gets too large
+fn blink_stone(number: u64) -> Option<Vec<u64>> {
+    if number == 0 {
+        return Some(vec![1]);
+    }
+
+    let digits = number.ilog10() + 1;
+    if digits.is_multiple_of(2) {
+        let split = 10u64.pow(digits / 2);
+        Some(vec![number / split, number % split])
+    } else {
+        number.checked_mul(2024).map(|number| vec![number])
+    }
+}
+
+fn parse_stones(input: &str) -> IResult<&str, Vec<u64>, ErrorTree<&str>> {
+    separated_list1(space1, digit1.map_res(str::parse))(input)
+}
+
+fn get_stones(input: &str) -> Result<Vec<u64>, ErrorTree<&str>> {
+    final_parser(parse_stones.terminated(line_ending.opt()).all_consuming())(input)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
-    #[ignore]
+    /// Blinks one stone at a time, keeping the whole line
+    fn naive_count_stones(input: &str, blinks: usize) -> u128 {
+        let mut stones = get_stones(input).unwrap();
+        for _ in 0..blinks {
+            stones = stones
+                .into_iter()
+                .flat_map(|number| blink_stone(number).unwrap())
+                .collect();
+        }
+        stones.len() as u128
+    }
+
+    #[test]
+    fn test_blink_stone() {
+        assert_eq!(blink_stone(0), Some(vec![1]));
+        assert_eq!(blink_stone(1), Some(vec![2024]));
+        assert_eq!(blink_stone(10), Some(vec![1, 0]));
+        assert_eq!(blink_stone(99), Some(vec![9, 9]));
+        assert_eq!(blink_stone(999), Some(vec![2021976]));
+        assert_eq!(blink_stone(1000), Some(vec![10, 0]));
+    }
+
+    #[test]
+    fn test_count_stones() {
+        assert_eq!(count_stones("0 1 10 99 999", 1).unwrap(), 7);
+        assert_eq!(count_stones("125 17", 6).unwrap(), 22);
+        assert!(matches!(
+            count_stones("125 x", 6),
+            Err(BlinkError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_count_stones_matches_naive() {
+        for input in ["125 17", "0 1 10 99 999", "8 4096 77 123456\n"] {
+            for blinks in 0..16 {
+                assert_eq!(
+                    count_stones(input, blinks).unwrap(),
+                    naive_count_stones(input, blinks),
+                    "{input} after {blinks} blinks"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(blink_stone(u64::MAX / 1000), None);
+        assert!(count_stones("125 17", 110).is_ok());
+        assert!(matches!(
+            count_stones("125 17", 300),
+            Err(BlinkError::Overflow { .. })
+        ));
+    }
+
     #[test]
     fn test_part1() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part1(input), "");
+        let input = "125 17";
+        assert_eq!(AdventPuzzle::part1(input), "55312");
     }
 
-    #[ignore]
     #[test]
     fn test_part2() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part2(input), "");
+        let input = "125 17";
+        assert_eq!(AdventPuzzle::part2(input), "65601038650482");
     }
 }
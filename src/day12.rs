@@ -1,14 +1,154 @@
 use crate::solution::Solution;
+use std::collections::{HashSet, VecDeque};
+use std::str::FromStr;
 
 pub struct AdventPuzzle;
 
 impl Solution for AdventPuzzle {
-    fn part1(_input: &str) -> String {
-        todo!()
+    fn part1(input: &str) -> String {
+        let garden = Garden::from_str(input).unwrap();
+        garden
+            .regions()
+            .iter()
+            .map(|region| region.area() * region.perimeter())
+            .sum::<usize>()
+            .to_string()
     }
 
-    fn part2(_input: &str) -> String {
-        todo!()
+    fn part2(input: &str) -> String {
+        let garden = Garden::from_str(input).unwrap();
+        garden
+            .regions()
+            .iter()
+            .map(|region| region.area() * region.sides())
+            .sum::<usize>()
+            .to_string()
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+struct Position {
+    row: isize,
+    column: isize,
+}
+
+impl Position {
+    fn offset(self, row: isize, column: isize) -> Self {
+        Self {
+            row: self.row + row,
+            column: self.column + column,
+        }
+    }
+
+    fn neighbours(self) -> [Position; 4] {
+        [
+            self.offset(-1, 0),
+            self.offset(1, 0),
+            self.offset(0, -1),
+            self.offset(0, 1),
+        ]
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Region {
+    plant: char,
+    plots: HashSet<Position>,
+}
+
+impl Region {
+    fn area(&self) -> usize {
+        self.plots.len()
+    }
+
+    /// Each side of a plot that doesn't touch another plot in the region needs fencing
+    fn perimeter(&self) -> usize {
+        self.plots
+            .iter()
+            .flat_map(|plot| plot.neighbours())
+            .filter(|neighbour| !self.plots.contains(neighbour))
+            .count()
+    }
+
+    /// A shape has as many sides as corners, which holes and all can be found plot by plot.
+    ///
+    /// Looking towards each diagonal from a plot, it's an outside corner if neither plot beside it
+    /// in that direction is in the region, and an inside corner if both are but the diagonal
+    /// isn't.
+    fn sides(&self) -> usize {
+        self.plots
+            .iter()
+            .map(|plot| {
+                [(-1, -1), (-1, 1), (1, -1), (1, 1)]
+                    .into_iter()
+                    .filter(|(row, column)| {
+                        let vertical = self.plots.contains(&plot.offset(*row, 0));
+                        let horizontal = self.plots.contains(&plot.offset(0, *column));
+                        let diagonal = self.plots.contains(&plot.offset(*row, *column));
+                        (!vertical && !horizontal) || (vertical && horizontal && !diagonal)
+                    })
+                    .count()
+            })
+            .sum()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Garden(Vec<Vec<char>>);
+
+impl Garden {
+    fn plant(&self, Position { row, column }: Position) -> Option<char> {
+        let row = self.0.get(usize::try_from(row).ok()?)?;
+        row.get(usize::try_from(column).ok()?).copied()
+    }
+
+    /// Flood fills from each plot not yet in a region to find the rest of its region
+    fn regions(&self) -> Vec<Region> {
+        let mut seen: HashSet<Position> = HashSet::new();
+        let mut regions = Vec::new();
+
+        for (row, plants) in self.0.iter().enumerate() {
+            for (column, plant) in plants.iter().enumerate() {
+                let start = Position {
+                    row: row as isize,
+                    column: column as isize,
+                };
+                if !seen.insert(start) {
+                    continue;
+                }
+
+                let mut plots = HashSet::from([start]);
+                let mut queue = VecDeque::from([start]);
+                while let Some(plot) = queue.pop_front() {
+                    for neighbour in plot.neighbours() {
+                        if self.plant(neighbour) == Some(*plant) && seen.insert(neighbour) {
+                            plots.insert(neighbour);
+                            queue.push_back(neighbour);
+                        }
+                    }
+                }
+                regions.push(Region {
+                    plant: *plant,
+                    plots,
+                });
+            }
+        }
+
+        regions
+    }
+}
+
+impl FromStr for Garden {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let garden = Self(
+            s.lines()
+                .filter(|line| !line.is_empty())
+                .map(|line| line.chars().collect())
+                .collect(),
+        );
+        Ok(garden)
     }
 }
 
@@ -16,17 +156,101 @@ impl Solution for AdventPuzzle {
 mod test {
     use super::*;
 
-    #[ignore]
+    fn measure(input: &str) -> Vec<(char, usize, usize, usize)> {
+        Garden::from_str(input)
+            .unwrap()
+            .regions()
+            .iter()
+            .map(|region| {
+                (
+                    region.plant,
+                    region.area(),
+                    region.perimeter(),
+                    region.sides(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_regions() {
+        let input = "AAAA
+BBCD
+BBCC
+EEEC";
+        assert_eq!(
+            measure(input),
+            vec![
+                ('A', 4, 10, 4),
+                ('B', 4, 8, 4),
+                ('C', 4, 10, 8),
+                ('D', 1, 4, 4),
+                ('E', 3, 8, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_regions() {
+        let input = "OOOOO
+OXOXO
+OOOOO
+OXOXO
+OOOOO";
+        let regions = measure(input);
+        assert_eq!(regions[0], ('O', 21, 36, 20));
+        assert!(regions[1..].iter().all(|region| *region == ('X', 1, 4, 4)));
+        assert_eq!(AdventPuzzle::part1(input), "772");
+        assert_eq!(AdventPuzzle::part2(input), "436");
+    }
+
+    #[test]
+    fn test_sides_with_holes() {
+        let input = "EEEEE
+EXXXX
+EEEEE
+EXXXX
+EEEEE";
+        assert_eq!(AdventPuzzle::part2(input), "236");
+
+        // The B regions touch diagonally but are separate, and make the A region's holes
+        let input = "AAAAAA
+AAABBA
+AAABBA
+ABBAAA
+ABBAAA
+AAAAAA";
+        assert_eq!(measure(input)[0], ('A', 28, 40, 12));
+        assert_eq!(AdventPuzzle::part2(input), "368");
+    }
+
     #[test]
     fn test_part1() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part1(input), "");
+        let input = "RRRRIICCFF
+RRRRIICCCF
+VVRRRCCFFF
+VVRCCCJFFF
+VVVVCJJCFE
+VVIVCCJJEE
+VVIIICJJEE
+MIIIIIJJEE
+MIIISIJEEE
+MMMISSJEEE";
+        assert_eq!(AdventPuzzle::part1(input), "1930");
     }
 
-    #[ignore]
     #[test]
     fn test_part2() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part2(input), "");
+        let input = "RRRRIICCFF
+RRRRIICCCF
+VVRRRCCFFF
+VVRCCCJFFF
+VVVVCJJCFE
+VVIVCCJJEE
+VVIIICJJEE
+MIIIIIJJEE
+MIIISIJEEE
+MMMISSJEEE";
+        assert_eq!(AdventPuzzle::part2(input), "1206");
     }
 }
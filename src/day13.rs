@@ -1,32 +1,391 @@
 use crate::solution::Solution;
+use nom::character::complete::{anychar, digit1, line_ending};
+use nom::multi::{count, separated_list1};
+use nom::sequence::{preceded, separated_pair, terminated, tuple};
+use nom::{IResult, Parser};
+use nom_supreme::error::ErrorTree;
+use nom_supreme::final_parser::final_parser;
+use nom_supreme::tag::complete::tag;
+use nom_supreme::ParserExt;
+use std::fmt::{Display, Formatter};
 
 pub struct AdventPuzzle;
 
 impl Solution for AdventPuzzle {
-    fn part1(_input: &str) -> String {
-        todo!()
+    fn part1(input: &str) -> String {
+        get_machines(input)
+            .expect("Could not parse claw machines")
+            .iter()
+            .filter_map(|machine| machine.cheapest_win(Some(MAX_PRESSES)))
+            .map(|presses| presses.tokens())
+            .sum::<i128>()
+            .to_string()
     }
 
-    fn part2(_input: &str) -> String {
-        todo!()
+    fn part2(input: &str) -> String {
+        get_machines(input)
+            .expect("Could not parse claw machines")
+            .iter()
+            .filter_map(|machine| machine.with_prize_offset(PRIZE_OFFSET).cheapest_win(None))
+            .map(|presses| presses.tokens())
+            .sum::<i128>()
+            .to_string()
     }
+
+    fn explain_part1(input: &str) -> Option<String> {
+        let machines = get_machines(input).expect("Could not parse claw machines");
+        Some(explain(&machines, Some(MAX_PRESSES)))
+    }
+
+    fn explain_part2(input: &str) -> Option<String> {
+        let machines: Vec<_> = get_machines(input)
+            .expect("Could not parse claw machines")
+            .iter()
+            .map(|machine| machine.with_prize_offset(PRIZE_OFFSET))
+            .collect();
+        Some(explain(&machines, None))
+    }
+}
+
+const MAX_PRESSES: i128 = 100;
+const PRIZE_OFFSET: i128 = 10_000_000_000_000;
+
+fn explain(machines: &[ClawMachine], max_presses: Option<i128>) -> String {
+    machines
+        .iter()
+        .enumerate()
+        .map(|(i, machine)| {
+            match (
+                machine.cheapest_win(max_presses),
+                machine.cheapest_win(None),
+            ) {
+                (Some(presses), _) => format!("Machine {}: {presses}", i + 1),
+                (None, Some(presses)) => {
+                    format!("Machine {}: {presses}, too many presses", i + 1)
+                }
+                (None, None) => format!("Machine {}: no way to win", i + 1),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct Vector {
+    x: i128,
+    y: i128,
+}
+
+impl Vector {
+    fn cross(self, other: Vector) -> i128 {
+        self.x * other.y - self.y * other.x
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct Presses {
+    a: i128,
+    b: i128,
+}
+
+impl Presses {
+    fn tokens(&self) -> i128 {
+        3 * self.a + self.b
+    }
+}
+
+impl Display for Presses {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "A pressed {} times, B pressed {} times, {} tokens",
+            self.a,
+            self.b,
+            self.tokens()
+        )
+    }
+}
+
+/// Greatest common divisor of `a` and `b` along with `s` and `t` where `a * s + b * t` is the gcd
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (gcd, s, t) = extended_gcd(b, a % b);
+        (gcd, t, s - (a / b) * t)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct ClawMachine {
+    a: Vector,
+    b: Vector,
+    prize: Vector,
+}
+
+impl ClawMachine {
+    fn with_prize_offset(&self, offset: i128) -> Self {
+        Self {
+            prize: Vector {
+                x: self.prize.x + offset,
+                y: self.prize.y + offset,
+            },
+            ..*self
+        }
+    }
+
+    /// The cheapest way to reach the prize pressing each button at most `max_presses` times, if
+    /// there is one.
+    ///
+    /// `a * A + b * B = prize` is two equations in two unknowns, so when the buttons move in
+    /// different directions Cramer's rule gives the only answer, which only counts if it's a
+    /// whole number of presses.
+    fn cheapest_win(&self, max_presses: Option<i128>) -> Option<Presses> {
+        let determinant = self.a.cross(self.b);
+        if determinant == 0 {
+            return self.cheapest_collinear_win(max_presses);
+        }
+
+        let a = self.prize.cross(self.b);
+        let b = self.a.cross(self.prize);
+        if a % determinant != 0 || b % determinant != 0 {
+            return None;
+        }
+
+        let presses = Presses {
+            a: a / determinant,
+            b: b / determinant,
+        };
+        let within = |count: i128| count >= 0 && max_presses.is_none_or(|max| count <= max);
+        (within(presses.a) && within(presses.b)).then_some(presses)
+    }
+
+    /// When both buttons move along the same line there can be many ways to reach the prize.
+    ///
+    /// Solving along one axis with the extended gcd gives one answer, and every other is found by
+    /// trading `B / gcd` presses of A for `A / gcd` presses of B. The cost changes linearly with
+    /// each trade, so the cheapest is at one end of the range where both counts stay positive
+    /// and within the limit.
+    fn cheapest_collinear_win(&self, max_presses: Option<i128>) -> Option<Presses> {
+        if self.a.cross(self.prize) != 0 || self.b.cross(self.prize) != 0 {
+            return None;
+        }
+
+        let (a, b, prize) = if self.a.x != 0 || self.b.x != 0 {
+            (self.a.x, self.b.x, self.prize.x)
+        } else if self.a.y != 0 || self.b.y != 0 {
+            (self.a.y, self.b.y, self.prize.y)
+        } else {
+            // Neither button moves the claw
+            return (self.prize == Vector { x: 0, y: 0 }).then_some(Presses { a: 0, b: 0 });
+        };
+
+        let (gcd, s, t) = extended_gcd(a, b);
+        if prize % gcd != 0 {
+            return None;
+        }
+        let (gcd, s, t) = if gcd < 0 { (-gcd, -s, -t) } else { (gcd, s, t) };
+        let a0 = s * (prize / gcd);
+        let b0 = t * (prize / gcd);
+        let a_step = b / gcd;
+        let b_step = a / gcd;
+
+        // a = a0 + k * a_step and b = b0 - k * b_step must both stay between zero and the limit
+        let mut lowest: Option<i128> = None;
+        let mut highest: Option<i128> = None;
+        let mut at_least = |bound: i128| lowest = Some(lowest.map_or(bound, |k| k.max(bound)));
+        let mut at_most = |bound: i128| highest = Some(highest.map_or(bound, |k| k.min(bound)));
+        let out_of_range = |count: i128| count < 0 || max_presses.is_some_and(|max| count > max);
+
+        if a_step > 0 {
+            at_least(-a0.div_euclid(a_step));
+            if let Some(max) = max_presses {
+                at_most((max - a0).div_euclid(a_step));
+            }
+        } else if out_of_range(a0) {
+            return None;
+        }
+        if b_step > 0 {
+            at_most(b0.div_euclid(b_step));
+            if let Some(max) = max_presses {
+                at_least(-(max - b0).div_euclid(b_step));
+            }
+        } else if out_of_range(b0) {
+            return None;
+        }
+
+        let cost_per_step = 3 * a_step - b_step;
+        let k = match (lowest, highest) {
+            (Some(lowest), _) if cost_per_step >= 0 => lowest,
+            (_, Some(highest)) => highest,
+            (Some(lowest), None) => lowest,
+            (None, None) => 0,
+        };
+        if lowest.is_some_and(|lowest| k < lowest) || highest.is_some_and(|highest| k > highest) {
+            return None;
+        }
+
+        Some(Presses {
+            a: a0 + k * a_step,
+            b: b0 - k * b_step,
+        })
+    }
+}
+
+fn parse_number(input: &str) -> IResult<&str, i128, ErrorTree<&str>> {
+    digit1.map_res(str::parse).parse(input)
+}
+
+fn parse_button(input: &str) -> IResult<&str, Vector, ErrorTree<&str>> {
+    preceded(
+        tuple((tag("Button "), anychar, tag(": X+"))),
+        separated_pair(parse_number, tag(", Y+"), parse_number),
+    )(input)
+    .map(|(remainder, (x, y))| (remainder, Vector { x, y }))
+}
+
+fn parse_prize(input: &str) -> IResult<&str, Vector, ErrorTree<&str>> {
+    preceded(
+        tag("Prize: X="),
+        separated_pair(parse_number, tag(", Y="), parse_number),
+    )(input)
+    .map(|(remainder, (x, y))| (remainder, Vector { x, y }))
+}
+
+fn parse_machine(input: &str) -> IResult<&str, ClawMachine, ErrorTree<&str>> {
+    tuple((
+        terminated(parse_button, line_ending),
+        terminated(parse_button, line_ending),
+        parse_prize,
+    ))(input)
+    .map(|(remainder, (a, b, prize))| (remainder, ClawMachine { a, b, prize }))
+}
+
+fn parse_machines(input: &str) -> IResult<&str, Vec<ClawMachine>, ErrorTree<&str>> {
+    separated_list1(count(line_ending, 2), parse_machine)(input)
+}
+
+fn get_machines(input: &str) -> Result<Vec<ClawMachine>, ErrorTree<&str>> {
+    final_parser(parse_machines.terminated(line_ending.opt()).all_consuming())(input)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    #[ignore]
+    fn machine(a: (i128, i128), b: (i128, i128), prize: (i128, i128)) -> ClawMachine {
+        ClawMachine {
+            a: Vector { x: a.0, y: a.1 },
+            b: Vector { x: b.0, y: b.1 },
+            prize: Vector {
+                x: prize.0,
+                y: prize.1,
+            },
+        }
+    }
+
+    #[test]
+    fn test_parse_machine() {
+        let input = "Button A: X+94, Y+34
+Button B: X+22, Y+67
+Prize: X=8400, Y=5400";
+        assert_eq!(
+            parse_machine(input).unwrap().1,
+            machine((94, 34), (22, 67), (8400, 5400))
+        );
+    }
+
+    #[test]
+    fn test_cheapest_win() {
+        assert_eq!(
+            machine((94, 34), (22, 67), (8400, 5400)).cheapest_win(None),
+            Some(Presses { a: 80, b: 40 })
+        );
+        assert_eq!(
+            machine((26, 66), (67, 21), (12748, 12176)).cheapest_win(None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cheapest_collinear_win() {
+        // B is cheaper per step, so use as many Bs as possible
+        assert_eq!(
+            machine((2, 4), (3, 6), (12, 24)).cheapest_win(None),
+            Some(Presses { a: 0, b: 4 })
+        );
+        // A moves more than three times as far as B, so As are cheaper
+        assert_eq!(
+            machine((8, 8), (2, 2), (18, 18)).cheapest_win(None),
+            Some(Presses { a: 2, b: 1 })
+        );
+        assert_eq!(machine((2, 4), (4, 8), (7, 14)).cheapest_win(None), None);
+        assert_eq!(machine((2, 4), (4, 8), (6, 13)).cheapest_win(None), None);
+        // Only moves vertically
+        assert_eq!(
+            machine((0, 5), (0, 1), (0, 11)).cheapest_win(None),
+            Some(Presses { a: 2, b: 1 })
+        );
+        assert_eq!(
+            machine((0, 0), (0, 0), (0, 0)).cheapest_win(None),
+            Some(Presses { a: 0, b: 0 })
+        );
+    }
+
+    #[test]
+    fn test_collinear_with_large_prize() {
+        let presses = machine((3, 3), (5, 5), (PRIZE_OFFSET + 1, PRIZE_OFFSET + 1))
+            .cheapest_win(None)
+            .unwrap();
+        assert_eq!(3 * presses.a + 5 * presses.b, PRIZE_OFFSET + 1);
+        assert_eq!(
+            presses,
+            Presses {
+                a: 2,
+                b: 2_000_000_000_000 - 1
+            }
+        );
+    }
+
     #[test]
     fn test_part1() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part1(input), "");
+        let input = "Button A: X+94, Y+34
+Button B: X+22, Y+67
+Prize: X=8400, Y=5400
+
+Button A: X+26, Y+66
+Button B: X+67, Y+21
+Prize: X=12748, Y=12176
+
+Button A: X+17, Y+86
+Button B: X+84, Y+37
+Prize: X=7870, Y=6450
+
+Button A: X+69, Y+23
+Button B: X+27, Y+71
+Prize: X=18641, Y=10279
+";
+        assert_eq!(AdventPuzzle::part1(input), "480");
     }
 
-    #[ignore]
     #[test]
     fn test_part2() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part2(input), "");
+        let input = "Button A: X+94, Y+34
+Button B: X+22, Y+67
+Prize: X=8400, Y=5400
+
+Button A: X+26, Y+66
+Button B: X+67, Y+21
+Prize: X=12748, Y=12176
+
+Button A: X+17, Y+86
+Button B: X+84, Y+37
+Prize: X=7870, Y=6450
+
+Button A: X+69, Y+23
+Button B: X+27, Y+71
+Prize: X=18641, Y=10279
+";
+        assert_eq!(AdventPuzzle::part2(input), "875318608908");
     }
 }
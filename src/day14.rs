@@ -1,32 +1,283 @@
 use crate::solution::Solution;
+use nom::character::complete::{i64, line_ending, space1};
+use nom::multi::separated_list1;
+use nom::sequence::{preceded, separated_pair};
+use nom::{IResult, Parser};
+use nom_supreme::error::ErrorTree;
+use nom_supreme::final_parser::final_parser;
+use nom_supreme::tag::complete::tag;
+use nom_supreme::ParserExt;
 
 pub struct AdventPuzzle;
 
 impl Solution for AdventPuzzle {
-    fn part1(_input: &str) -> String {
-        todo!()
+    fn part1(input: &str) -> String {
+        safety_factor_after_100(input, BATHROOM).to_string()
     }
 
-    fn part2(_input: &str) -> String {
-        todo!()
+    fn part2(input: &str) -> String {
+        seconds_to_tree(input, BATHROOM).to_string()
     }
+
+    fn explain_part1(input: &str) -> Option<String> {
+        let robots = get_robots(input).expect("Could not parse robots");
+        Some(BATHROOM.render(&BATHROOM.positions_at(&robots, 100)))
+    }
+
+    fn explain_part2(input: &str) -> Option<String> {
+        let robots = get_robots(input).expect("Could not parse robots");
+        let seconds = BATHROOM.find_tree(&robots);
+        Some(BATHROOM.render(&BATHROOM.positions_at(&robots, seconds)))
+    }
+}
+
+const BATHROOM: Bathroom = Bathroom {
+    width: 101,
+    height: 103,
+};
+
+/// Safety factor after 100 seconds, in a bathroom of the given size
+fn safety_factor_after_100(input: &str, bathroom: Bathroom) -> usize {
+    let robots = get_robots(input).expect("Could not parse robots");
+    bathroom.safety_factor(&bathroom.positions_at(&robots, 100))
+}
+
+/// Seconds until the robots draw the tree, in a bathroom of the given size
+fn seconds_to_tree(input: &str, bathroom: Bathroom) -> i64 {
+    let robots = get_robots(input).expect("Could not parse robots");
+    bathroom.find_tree(&robots)
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+struct Vector {
+    x: i64,
+    y: i64,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct Robot {
+    position: Vector,
+    velocity: Vector,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct Bathroom {
+    width: i64,
+    height: i64,
+}
+
+impl Bathroom {
+    /// Robots wrap around the edges, so they can be moved straight to any time
+    fn positions_at(&self, robots: &[Robot], seconds: i64) -> Vec<Vector> {
+        robots
+            .iter()
+            .map(|robot| Vector {
+                x: (robot.position.x + robot.velocity.x * seconds).rem_euclid(self.width),
+                y: (robot.position.y + robot.velocity.y * seconds).rem_euclid(self.height),
+            })
+            .collect()
+    }
+
+    /// Robots in each quadrant multiplied together, robots on the middle lines don't count
+    fn safety_factor(&self, positions: &[Vector]) -> usize {
+        let middle_x = self.width / 2;
+        let middle_y = self.height / 2;
+        let mut quadrants = [0; 4];
+        for position in positions {
+            if position.x == middle_x || position.y == middle_y {
+                continue;
+            }
+            let quadrant = (position.x > middle_x) as usize + 2 * (position.y > middle_y) as usize;
+            quadrants[quadrant] += 1;
+        }
+        quadrants.iter().product()
+    }
+
+    /// How spread out the robots are, the sum of the variance in each direction
+    fn spread(positions: &[Vector]) -> f64 {
+        let count = positions.len() as f64;
+        let variance = |values: Vec<f64>| {
+            let mean = values.iter().sum::<f64>() / count;
+            values
+                .iter()
+                .map(|value| (value - mean).powi(2))
+                .sum::<f64>()
+                / count
+        };
+        variance(positions.iter().map(|p| p.x as f64).collect())
+            + variance(positions.iter().map(|p| p.y as f64).collect())
+    }
+
+    /// The first time the robots bunch up the most, which is when they draw the tree.
+    ///
+    /// Every robot is back where it started after `width * height` seconds, so only that many
+    /// frames need checking.
+    fn find_tree(&self, robots: &[Robot]) -> i64 {
+        (0..self.width * self.height)
+            .map(|seconds| (seconds, Self::spread(&self.positions_at(robots, seconds))))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(seconds, _)| seconds)
+            .unwrap_or_default()
+    }
+
+    /// Shows how many robots are on each tile, `.` where there are none
+    fn render(&self, positions: &[Vector]) -> String {
+        let mut counts = vec![vec![0; self.width as usize]; self.height as usize];
+        for position in positions {
+            counts[position.y as usize][position.x as usize] += 1;
+        }
+        counts
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|count| match count {
+                        0 => '.',
+                        count => char::from_digit(*count, 10).unwrap_or('*'),
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn parse_vector(input: &str) -> IResult<&str, Vector, ErrorTree<&str>> {
+    separated_pair(i64, tag(","), i64)
+        .map(|(x, y)| Vector { x, y })
+        .parse(input)
+}
+
+fn parse_robot(input: &str) -> IResult<&str, Robot, ErrorTree<&str>> {
+    separated_pair(
+        preceded(tag("p="), parse_vector),
+        space1,
+        preceded(tag("v="), parse_vector),
+    )
+    .map(|(position, velocity)| Robot { position, velocity })
+    .parse(input)
+}
+
+fn get_robots(input: &str) -> Result<Vec<Robot>, ErrorTree<&str>> {
+    final_parser(
+        separated_list1(line_ending, parse_robot)
+            .terminated(line_ending.opt())
+            .all_consuming(),
+    )(input)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    #[ignore]
+    const EXAMPLE_BATHROOM: Bathroom = Bathroom {
+        width: 11,
+        height: 7,
+    };
+
+    const EXAMPLE: &str = "p=0,4 v=3,-3
+p=6,3 v=-1,-3
+p=10,3 v=-1,2
+p=2,0 v=2,-1
+p=0,0 v=1,3
+p=3,0 v=-2,-2
+p=7,6 v=-1,-3
+p=3,0 v=-1,-2
+p=9,3 v=2,3
+p=7,3 v=-1,2
+p=2,4 v=2,-3
+p=9,5 v=-3,-3
+";
+
+    #[test]
+    fn test_parse_robot() {
+        assert_eq!(
+            parse_robot("p=2,4 v=2,-3").unwrap().1,
+            Robot {
+                position: Vector { x: 2, y: 4 },
+                velocity: Vector { x: 2, y: -3 },
+            }
+        );
+    }
+
+    #[test]
+    fn test_positions_at() {
+        let robot = parse_robot("p=2,4 v=2,-3").unwrap().1;
+        let positions: Vec<_> = (0..=5)
+            .map(|seconds| EXAMPLE_BATHROOM.positions_at(&[robot], seconds)[0])
+            .map(|Vector { x, y }| (x, y))
+            .collect();
+        assert_eq!(
+            positions,
+            vec![(2, 4), (4, 1), (6, 5), (8, 2), (10, 6), (1, 3)]
+        );
+    }
+
+    #[test]
+    fn test_render_and_safety_factor() {
+        let robots = get_robots(EXAMPLE).unwrap();
+        let positions = EXAMPLE_BATHROOM.positions_at(&robots, 100);
+        assert_eq!(
+            EXAMPLE_BATHROOM.render(&positions),
+            "......2..1.
+...........
+1..........
+.11........
+.....1.....
+...12......
+.1....1...."
+        );
+        assert_eq!(EXAMPLE_BATHROOM.safety_factor(&positions), 12);
+    }
+
+    #[test]
+    fn test_find_tree() {
+        // Robots that all end up in a 3x3 block after 4321 seconds
+        let seconds = 4321;
+        let robots: Vec<_> = (0..60)
+            .map(|i| {
+                let velocity = Vector {
+                    x: (i * 7) % 23 - 11,
+                    y: (i * 13) % 29 - 14,
+                };
+                let target = Vector {
+                    x: 50 + i % 3,
+                    y: 50 + (i / 3) % 3,
+                };
+                Robot {
+                    position: Vector {
+                        x: (target.x - velocity.x * seconds).rem_euclid(BATHROOM.width),
+                        y: (target.y - velocity.y * seconds).rem_euclid(BATHROOM.height),
+                    },
+                    velocity,
+                }
+            })
+            .collect();
+        assert_eq!(BATHROOM.find_tree(&robots), seconds);
+    }
+
     #[test]
     fn test_part1() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part1(input), "");
+        assert_eq!(safety_factor_after_100(EXAMPLE, EXAMPLE_BATHROOM), 12);
     }
 
-    #[ignore]
     #[test]
     fn test_part2() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part2(input), "");
+        // The example never draws anything, so send robots into a 3x3 block after 50 seconds
+        let seconds: i64 = 50;
+        let input: Vec<String> = (0..9i64)
+            .map(|i| {
+                let (vx, vy) = (i % 5 - 2, i % 7 - 3);
+                let (x, y) = (4 + i % 3, 2 + i / 3);
+                format!(
+                    "p={},{} v={vx},{vy}",
+                    (x - vx * seconds).rem_euclid(EXAMPLE_BATHROOM.width),
+                    (y - vy * seconds).rem_euclid(EXAMPLE_BATHROOM.height)
+                )
+            })
+            .collect();
+        assert_eq!(
+            seconds_to_tree(&input.join("\n"), EXAMPLE_BATHROOM),
+            seconds
+        );
     }
 }
@@ -1,32 +1,494 @@
 use crate::solution::Solution;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 pub struct AdventPuzzle;
 
 impl Solution for AdventPuzzle {
-    fn part1(_input: &str) -> String {
-        todo!()
+    fn part1(input: &str) -> String {
+        let (mut warehouse, moves) = parse_input(input);
+        for direction in moves {
+            warehouse.move_robot(direction);
+        }
+        warehouse.gps_sum().to_string()
     }
 
-    fn part2(_input: &str) -> String {
-        todo!()
+    fn part2(input: &str) -> String {
+        let (warehouse, moves) = parse_input(input);
+        let mut warehouse = warehouse.widen();
+        for direction in moves {
+            warehouse.move_robot(direction);
+        }
+        warehouse.gps_sum().to_string()
+    }
+
+    fn explain_part1(input: &str) -> Option<String> {
+        let (warehouse, moves) = parse_input(input);
+        Some(explain(warehouse, &moves))
+    }
+
+    fn explain_part2(input: &str) -> Option<String> {
+        let (warehouse, moves) = parse_input(input);
+        Some(explain(warehouse.widen(), &moves))
+    }
+}
+
+/// The warehouse before and after each move
+fn explain(mut warehouse: Warehouse, moves: &[Direction]) -> String {
+    let mut steps = vec![format!("Initial state:\n{warehouse}")];
+    for direction in moves {
+        warehouse.move_robot(*direction);
+        steps.push(format!("Move {direction}:\n{warehouse}"));
+    }
+    steps.join("\n\n")
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn is_vertical(self) -> bool {
+        matches!(self, Direction::Up | Direction::Down)
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '^' => Ok(Direction::Up),
+            'v' => Ok(Direction::Down),
+            '<' => Ok(Direction::Left),
+            '>' => Ok(Direction::Right),
+            c => Err(c),
+        }
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Direction::Up => write!(f, "^"),
+            Direction::Down => write!(f, "v"),
+            Direction::Left => write!(f, "<"),
+            Direction::Right => write!(f, ">"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+struct Position {
+    row: usize,
+    column: usize,
+}
+
+impl Position {
+    /// The warehouse is walled in, so there's always somewhere to step to
+    fn step(self, direction: Direction) -> Self {
+        let Position { row, column } = self;
+        match direction {
+            Direction::Up => Position {
+                row: row - 1,
+                column,
+            },
+            Direction::Down => Position {
+                row: row + 1,
+                column,
+            },
+            Direction::Left => Position {
+                row,
+                column: column - 1,
+            },
+            Direction::Right => Position {
+                row,
+                column: column + 1,
+            },
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Tile {
+    Empty,
+    Wall,
+    Robot,
+    Box,
+    BoxLeft,
+    BoxRight,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct Warehouse {
+    tiles: Vec<Vec<Tile>>,
+    robot: Position,
+}
+
+impl Warehouse {
+    fn tile(&self, Position { row, column }: Position) -> Tile {
+        self.tiles[row][column]
+    }
+
+    fn set_tile(&mut self, Position { row, column }: Position, tile: Tile) {
+        self.tiles[row][column] = tile;
+    }
+
+    /// Everything twice as wide except the robot, boxes become `[]`
+    fn widen(&self) -> Warehouse {
+        let tiles = self
+            .tiles
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .flat_map(|tile| match tile {
+                        Tile::Wall => [Tile::Wall, Tile::Wall],
+                        Tile::Box => [Tile::BoxLeft, Tile::BoxRight],
+                        Tile::Robot => [Tile::Robot, Tile::Empty],
+                        _ => [Tile::Empty, Tile::Empty],
+                    })
+                    .collect()
+            })
+            .collect();
+        Warehouse {
+            tiles,
+            robot: Position {
+                row: self.robot.row,
+                column: self.robot.column * 2,
+            },
+        }
+    }
+
+    /// Everything the robot would shove when moving, itself first, or nothing if something
+    /// runs into a wall.
+    ///
+    /// Wide boxes moving up or down push with both halves, so a box can be reached from two
+    /// boxes below it. Each position is only checked once, and since every push is one step
+    /// further from the robot they come out nearest first.
+    fn pushed(&self, direction: Direction) -> Option<Vec<Position>> {
+        let mut pushed = vec![self.robot];
+        let mut seen = HashSet::from([self.robot]);
+        let mut index = 0;
+        while let Some(&position) = pushed.get(index) {
+            index += 1;
+            let next = position.step(direction);
+            let neighbours = match self.tile(next) {
+                Tile::Empty => vec![],
+                Tile::Wall => return None,
+                Tile::BoxLeft if direction.is_vertical() => {
+                    vec![next, next.step(Direction::Right)]
+                }
+                Tile::BoxRight if direction.is_vertical() => {
+                    vec![next, next.step(Direction::Left)]
+                }
+                _ => vec![next],
+            };
+            for neighbour in neighbours {
+                if seen.insert(neighbour) {
+                    pushed.push(neighbour);
+                }
+            }
+        }
+        Some(pushed)
+    }
+
+    fn move_robot(&mut self, direction: Direction) {
+        if let Some(pushed) = self.pushed(direction) {
+            // Furthest first, so nothing is moved onto a tile that hasn't been cleared yet
+            for position in pushed.into_iter().rev() {
+                self.set_tile(position.step(direction), self.tile(position));
+                self.set_tile(position, Tile::Empty);
+            }
+            self.robot = self.robot.step(direction);
+        }
+    }
+
+    /// Boxes are measured from the top and left edges to their left side
+    fn gps_sum(&self) -> usize {
+        self.tiles
+            .iter()
+            .enumerate()
+            .flat_map(|(row, tiles)| {
+                tiles
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, tile)| matches!(tile, Tile::Box | Tile::BoxLeft))
+                    .map(move |(column, _)| 100 * row + column)
+            })
+            .sum()
+    }
+}
+
+impl Display for Warehouse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let rows: Vec<String> = self
+            .tiles
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|tile| match tile {
+                        Tile::Empty => '.',
+                        Tile::Wall => '#',
+                        Tile::Robot => '@',
+                        Tile::Box => 'O',
+                        Tile::BoxLeft => '[',
+                        Tile::BoxRight => ']',
+                    })
+                    .collect()
+            })
+            .collect();
+        write!(f, "{}", rows.join("\n"))
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum WarehouseError {
+    InvalidTile(char),
+    MissingRobot,
+    TooManyRobots,
+}
+
+impl Display for WarehouseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WarehouseError::InvalidTile(c) => write!(f, "invalid tile {c:?}"),
+            WarehouseError::MissingRobot => write!(f, "missing robot"),
+            WarehouseError::TooManyRobots => write!(f, "more than one robot"),
+        }
+    }
+}
+
+impl Error for WarehouseError {}
+
+impl FromStr for Warehouse {
+    type Err = WarehouseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut robot = None;
+        let tiles = s
+            .lines()
+            .enumerate()
+            .map(|(row, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(column, c)| match c {
+                        '.' => Ok(Tile::Empty),
+                        '#' => Ok(Tile::Wall),
+                        'O' => Ok(Tile::Box),
+                        '[' => Ok(Tile::BoxLeft),
+                        ']' => Ok(Tile::BoxRight),
+                        '@' => {
+                            if robot.replace(Position { row, column }).is_some() {
+                                return Err(WarehouseError::TooManyRobots);
+                            }
+                            Ok(Tile::Robot)
+                        }
+                        c => Err(WarehouseError::InvalidTile(c)),
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Warehouse {
+            tiles,
+            robot: robot.ok_or(WarehouseError::MissingRobot)?,
+        })
     }
 }
 
+/// The map, a blank line, then the moves which may be split over several lines
+fn parse_input(input: &str) -> (Warehouse, Vec<Direction>) {
+    let input = input.replace("\r\n", "\n");
+    let (map, moves) = input
+        .split_once("\n\n")
+        .expect("Map and moves should be separated by a blank line");
+    let warehouse = Warehouse::from_str(map).expect("Invalid warehouse");
+    let moves = moves
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(Direction::try_from)
+        .collect::<Result<_, _>>()
+        .expect("Invalid move");
+    (warehouse, moves)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
-    #[ignore]
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Warehouse::from_str("#####\n#.O.#\n#####"),
+            Err(WarehouseError::MissingRobot)
+        );
+        assert_eq!(
+            Warehouse::from_str("#####\n#@.@#\n#####"),
+            Err(WarehouseError::TooManyRobots)
+        );
+        assert_eq!(
+            Warehouse::from_str("#####\n#@x.#\n#####"),
+            Err(WarehouseError::InvalidTile('x'))
+        );
+    }
+
+    #[test]
+    fn test_widen() {
+        let warehouse = Warehouse::from_str(
+            "#######
+#...#.#
+#.....#
+#..OO@#
+#..O..#
+#.....#
+#######",
+        )
+        .unwrap();
+        assert_eq!(
+            warehouse.widen().to_string(),
+            "##############
+##......##..##
+##..........##
+##....[][]@.##
+##....[]....##
+##..........##
+##############"
+        );
+    }
+
+    #[test]
+    fn test_push_wide_boxes() {
+        let input = "#######
+#...#.#
+#.....#
+#..OO@#
+#..O..#
+#.....#
+#######
+
+<vv<<^^<<^^";
+        let (warehouse, moves) = parse_input(input);
+        let mut warehouse = warehouse.widen();
+        for direction in moves {
+            warehouse.move_robot(direction);
+        }
+        assert_eq!(
+            warehouse.to_string(),
+            "##############
+##...[].##..##
+##...@.[]...##
+##....[]....##
+##..........##
+##..........##
+##############"
+        );
+        assert_eq!(warehouse.gps_sum(), 618);
+    }
+
+    #[test]
+    fn test_blocked_wide_box() {
+        // The right hand box is stuck under a wall, so neither box can move
+        let mut warehouse = Warehouse::from_str(
+            "##########
+##....#.##
+##..[][]##
+##...[].##
+##...@..##
+##########",
+        )
+        .unwrap();
+        let before = warehouse.clone();
+        warehouse.move_robot(Direction::Up);
+        assert_eq!(warehouse, before);
+    }
+
+    #[test]
+    fn test_push_tall_stack() {
+        // Boxes from the same column line up once widened, and each box is only checked once so a
+        // stack of 40 of them pushes in linear time
+        let height = 40;
+        let mut map = vec!["#####".to_string(), "#...#".to_string()];
+        map.extend((0..height).map(|_| "#.O.#".to_string()));
+        map.extend(["#.@.#".to_string(), "#####".to_string()]);
+        let mut warehouse = Warehouse::from_str(&map.join("\n")).unwrap().widen();
+        let before = warehouse.gps_sum();
+        warehouse.move_robot(Direction::Up);
+        assert_eq!(warehouse.gps_sum(), before - 100 * height);
+        warehouse.move_robot(Direction::Up);
+        assert_eq!(warehouse.gps_sum(), before - 100 * height);
+    }
+
+    #[test]
+    fn test_explain() {
+        let (warehouse, moves) = parse_input("#####\n#@O.#\n#####\n\n>\n>");
+        assert_eq!(
+            explain(warehouse, &moves),
+            "Initial state:
+#####
+#@O.#
+#####
+
+Move >:
+#####
+#.@O#
+#####
+
+Move >:
+#####
+#.@O#
+#####"
+        );
+    }
+
     #[test]
     fn test_part1() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part1(input), "");
+        let input = "########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########
+
+<^^>>>vv<v>>v<<
+";
+        let (mut warehouse, moves) = parse_input(input);
+        for direction in moves {
+            warehouse.move_robot(direction);
+        }
+        assert_eq!(
+            warehouse.to_string(),
+            "########
+#....OO#
+##.....#
+#.....O#
+#.#O@..#
+#...O..#
+#...O..#
+########"
+        );
+        assert_eq!(AdventPuzzle::part1(input), "2028");
     }
 
-    #[ignore]
     #[test]
     fn test_part2() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part2(input), "");
+        let input = "#######
+#...#.#
+#.....#
+#..OO@#
+#..O..#
+#.....#
+#######
+
+<vv<<^^<<^^
+";
+        assert_eq!(AdventPuzzle::part2(input), "618");
     }
 }
@@ -1,14 +1,302 @@
 use crate::solution::Solution;
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 pub struct AdventPuzzle;
 
 impl Solution for AdventPuzzle {
-    fn part1(_input: &str) -> String {
-        todo!()
+    fn part1(input: &str) -> String {
+        let maze = Maze::from_str(input).expect("Invalid maze");
+        maze.best_paths()
+            .expect("There should be a path to the end")
+            .score
+            .to_string()
     }
 
-    fn part2(_input: &str) -> String {
-        todo!()
+    fn part2(input: &str) -> String {
+        let maze = Maze::from_str(input).expect("Invalid maze");
+        maze.best_paths()
+            .expect("There should be a path to the end")
+            .tiles
+            .len()
+            .to_string()
+    }
+
+    fn explain_part2(input: &str) -> Option<String> {
+        let maze = Maze::from_str(input).ok()?;
+        let best = maze.best_paths()?;
+        Some(format!(
+            "Best score {} with {} tiles on a best path:\n{}",
+            best.score,
+            best.tiles.len(),
+            maze.render(&best.tiles)
+        ))
+    }
+}
+
+const STEP_COST: u64 = 1;
+const TURN_COST: u64 = 1000;
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+enum Facing {
+    East,
+    South,
+    West,
+    North,
+}
+
+impl Facing {
+    fn clockwise(self) -> Self {
+        match self {
+            Facing::East => Facing::South,
+            Facing::South => Facing::West,
+            Facing::West => Facing::North,
+            Facing::North => Facing::East,
+        }
+    }
+
+    fn counter_clockwise(self) -> Self {
+        match self {
+            Facing::East => Facing::North,
+            Facing::North => Facing::West,
+            Facing::West => Facing::South,
+            Facing::South => Facing::East,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+struct Position {
+    row: usize,
+    column: usize,
+}
+
+impl Position {
+    /// The maze is walled in, so there's always somewhere to step to
+    fn step(self, facing: Facing) -> Self {
+        let Position { row, column } = self;
+        match facing {
+            Facing::East => Position {
+                row,
+                column: column + 1,
+            },
+            Facing::South => Position {
+                row: row + 1,
+                column,
+            },
+            Facing::West => Position {
+                row,
+                column: column - 1,
+            },
+            Facing::North => Position {
+                row: row - 1,
+                column,
+            },
+        }
+    }
+}
+
+/// Where the reindeer is and which way it's facing
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+struct State {
+    position: Position,
+    facing: Facing,
+}
+
+/// The lowest score through the maze and every tile on any path achieving it
+#[derive(Debug)]
+struct BestPaths {
+    score: u64,
+    tiles: HashSet<Position>,
+}
+
+#[derive(Debug)]
+struct Maze {
+    walls: Vec<Vec<bool>>,
+    start: Position,
+    end: Position,
+}
+
+impl Maze {
+    fn is_wall(&self, Position { row, column }: Position) -> bool {
+        self.walls[row][column]
+    }
+
+    /// Stepping forward or turning on the spot, with what each costs
+    fn moves(&self, state: State) -> impl Iterator<Item = (State, u64)> {
+        let forward = state.position.step(state.facing);
+        let step = (!self.is_wall(forward)).then_some((
+            State {
+                position: forward,
+                facing: state.facing,
+            },
+            STEP_COST,
+        ));
+        let turns = [state.facing.clockwise(), state.facing.counter_clockwise()]
+            .map(|facing| (State { facing, ..state }, TURN_COST));
+        step.into_iter().chain(turns)
+    }
+
+    /// Dijkstra over (position, facing), remembering every predecessor that reached a state at
+    /// its lowest cost so all the best paths can be walked back from the end.
+    fn best_paths(&self) -> Option<BestPaths> {
+        let start = State {
+            position: self.start,
+            facing: Facing::East,
+        };
+        let mut costs = HashMap::from([(start, 0)]);
+        let mut predecessors: HashMap<State, Vec<State>> = HashMap::new();
+        let mut queue = BinaryHeap::from([Reverse((0, start))]);
+        let mut score = None;
+
+        while let Some(Reverse((cost, state))) = queue.pop() {
+            if costs.get(&state).is_some_and(|&best| cost > best) {
+                continue;
+            }
+            if score.is_some_and(|score| cost > score) {
+                break;
+            }
+            if state.position == self.end {
+                score = Some(cost);
+                continue;
+            }
+            for (next, step_cost) in self.moves(state) {
+                let next_cost = cost + step_cost;
+                match costs.entry(next) {
+                    Entry::Occupied(mut entry) => {
+                        if next_cost < *entry.get() {
+                            entry.insert(next_cost);
+                            predecessors.insert(next, vec![state]);
+                            queue.push(Reverse((next_cost, next)));
+                        } else if next_cost == *entry.get() {
+                            predecessors.entry(next).or_default().push(state);
+                        }
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(next_cost);
+                        predecessors.insert(next, vec![state]);
+                        queue.push(Reverse((next_cost, next)));
+                    }
+                }
+            }
+        }
+
+        let score = score?;
+        let mut stack: Vec<State> = costs
+            .iter()
+            .filter(|(state, &cost)| state.position == self.end && cost == score)
+            .map(|(state, _)| *state)
+            .collect();
+        let mut seen: HashSet<State> = stack.iter().copied().collect();
+        while let Some(state) = stack.pop() {
+            for previous in predecessors.get(&state).into_iter().flatten() {
+                if seen.insert(*previous) {
+                    stack.push(*previous);
+                }
+            }
+        }
+
+        Some(BestPaths {
+            score,
+            tiles: seen.into_iter().map(|state| state.position).collect(),
+        })
+    }
+
+    /// The maze with the given tiles marked `O`, as in the puzzle
+    fn render(&self, tiles: &HashSet<Position>) -> String {
+        self.walls
+            .iter()
+            .enumerate()
+            .map(|(row, walls)| {
+                walls
+                    .iter()
+                    .enumerate()
+                    .map(|(column, &wall)| {
+                        let position = Position { row, column };
+                        if wall {
+                            '#'
+                        } else if tiles.contains(&position) {
+                            'O'
+                        } else if position == self.start {
+                            'S'
+                        } else if position == self.end {
+                            'E'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum MazeError {
+    InvalidTile(char),
+    MissingStart,
+    TooManyStarts,
+    MissingEnd,
+    TooManyEnds,
+}
+
+impl Display for MazeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MazeError::InvalidTile(c) => write!(f, "invalid tile {c:?}"),
+            MazeError::MissingStart => write!(f, "missing start"),
+            MazeError::TooManyStarts => write!(f, "more than one start"),
+            MazeError::MissingEnd => write!(f, "missing end"),
+            MazeError::TooManyEnds => write!(f, "more than one end"),
+        }
+    }
+}
+
+impl Error for MazeError {}
+
+impl FromStr for Maze {
+    type Err = MazeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut start = None;
+        let mut end = None;
+        let walls = s
+            .lines()
+            .enumerate()
+            .map(|(row, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(column, c)| match c {
+                        '#' => Ok(true),
+                        '.' => Ok(false),
+                        'S' => {
+                            if start.replace(Position { row, column }).is_some() {
+                                return Err(MazeError::TooManyStarts);
+                            }
+                            Ok(false)
+                        }
+                        'E' => {
+                            if end.replace(Position { row, column }).is_some() {
+                                return Err(MazeError::TooManyEnds);
+                            }
+                            Ok(false)
+                        }
+                        c => Err(MazeError::InvalidTile(c)),
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Maze {
+            walls,
+            start: start.ok_or(MazeError::MissingStart)?,
+            end: end.ok_or(MazeError::MissingEnd)?,
+        })
     }
 }
 
@@ -16,17 +304,95 @@ impl Solution for AdventPuzzle {
 mod test {
     use super::*;
 
-    #[ignore]
+    const FIRST_EXAMPLE: &str = "###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############
+";
+
+    const SECOND_EXAMPLE: &str = "#################
+#...#...#...#..E#
+#.#.#.#.#.#.#.#.#
+#.#.#.#...#...#.#
+#.#.#.#.###.#.#.#
+#...#.#.#.....#.#
+#.#.#.#.#.#####.#
+#.#...#.#.#.....#
+#.#.#####.#.###.#
+#.#.#.......#...#
+#.#.###.#####.###
+#.#.#...#.....#.#
+#.#.#.#####.###.#
+#.#.#.........#.#
+#.#.#.#########.#
+#S#.............#
+#################
+";
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Maze::from_str("#####\n#S..#\n#####").unwrap_err(),
+            MazeError::MissingEnd
+        );
+        assert_eq!(
+            Maze::from_str("#####\n#..E#\n#####").unwrap_err(),
+            MazeError::MissingStart
+        );
+        assert_eq!(
+            Maze::from_str("#####\n#SSE#\n#####").unwrap_err(),
+            MazeError::TooManyStarts
+        );
+        assert_eq!(
+            Maze::from_str("#####\n#S?E#\n#####").unwrap_err(),
+            MazeError::InvalidTile('?')
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let maze = Maze::from_str(FIRST_EXAMPLE).unwrap();
+        let best = maze.best_paths().unwrap();
+        assert_eq!(
+            maze.render(&best.tiles),
+            "###############
+#.......#....O#
+#.#.###.#.###O#
+#.....#.#...#O#
+#.###.#####.#O#
+#.#.#.......#O#
+#.#.#####.###O#
+#..OOOOOOOOO#O#
+###O#O#####O#O#
+#OOO#O....#O#O#
+#O#O#O###.#O#O#
+#OOOOO#...#O#O#
+#O###.#.#.#O#O#
+#O..#.....#OOO#
+###############"
+        );
+    }
+
     #[test]
     fn test_part1() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part1(input), "");
+        assert_eq!(AdventPuzzle::part1(FIRST_EXAMPLE), "7036");
+        assert_eq!(AdventPuzzle::part1(SECOND_EXAMPLE), "11048");
     }
 
-    #[ignore]
     #[test]
     fn test_part2() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part2(input), "");
+        assert_eq!(AdventPuzzle::part2(FIRST_EXAMPLE), "45");
+        assert_eq!(AdventPuzzle::part2(SECOND_EXAMPLE), "64");
     }
 }
@@ -1,32 +1,431 @@
 use crate::solution::Solution;
+use nom::character::complete::{digit1, line_ending, one_of};
+use nom::multi::separated_list1;
+use nom::sequence::{delimited, preceded, tuple};
+use nom::{IResult, Parser};
+use nom_supreme::error::ErrorTree;
+use nom_supreme::final_parser::final_parser;
+use nom_supreme::tag::complete::tag;
+use nom_supreme::ParserExt;
 
 pub struct AdventPuzzle;
 
 impl Solution for AdventPuzzle {
-    fn part1(_input: &str) -> String {
-        todo!()
+    fn part1(input: &str) -> String {
+        let mut computer = get_computer(input).expect("Could not parse computer");
+        computer
+            .run()
+            .iter()
+            .map(u8::to_string)
+            .collect::<Vec<_>>()
+            .join(",")
     }
 
-    fn part2(_input: &str) -> String {
-        todo!()
+    fn part2(input: &str) -> String {
+        let computer = get_computer(input).expect("Could not parse computer");
+        find_quine(&computer)
+            .expect("No value of register A outputs the program")
+            .to_string()
     }
+
+    fn explain_part1(input: &str) -> Option<String> {
+        let computer = get_computer(input).ok()?;
+        Some(trace(computer))
+    }
+
+    fn explain_part2(input: &str) -> Option<String> {
+        let computer = get_computer(input).ok()?;
+        let a = find_quine(&computer)?;
+        Some(format!(
+            "{}\n\nRegister A = {a} ({a:o} in octal) outputs the program",
+            disassemble(computer.program())
+        ))
+    }
+}
+
+/// The smallest register A that makes the program output a copy of itself.
+///
+/// Programs like this shift A right three bits each loop and output something depending on the
+/// low bits, so the last output only depends on the highest octal digit of A. Working backwards
+/// we fix one more octal digit per output, trying the smaller digits first and backtracking when
+/// none of them gives the right suffix.
+fn find_quine(computer: &Computer) -> Option<u64> {
+    fn search(computer: &Computer, a: u64, remaining: usize) -> Option<u64> {
+        if remaining == 0 {
+            return Some(a);
+        }
+        let expected = &computer.program()[remaining - 1..];
+        (0..8).map(|digit| a << 3 | digit).find_map(|candidate| {
+            let mut attempt = Computer::new(
+                Registers {
+                    a: candidate,
+                    ..computer.registers()
+                },
+                computer.program().to_vec(),
+            );
+            (attempt.run() == expected)
+                .then(|| search(computer, candidate, remaining - 1))
+                .flatten()
+        })
+    }
+
+    search(computer, 0, computer.program().len())
+}
+
+/// How a combo operand reads in pseudo-code
+fn combo_name(operand: u8) -> String {
+    match operand {
+        0..=3 => operand.to_string(),
+        4 => "a".to_string(),
+        5 => "b".to_string(),
+        6 => "c".to_string(),
+        _ => "<reserved>".to_string(),
+    }
+}
+
+/// The program as pseudo-code, one instruction per line with its address
+fn disassemble(program: &[u8]) -> String {
+    program
+        .chunks_exact(2)
+        .enumerate()
+        .map(|(index, pair)| {
+            let operand = pair[1];
+            let code = match Instruction::from(pair[0]) {
+                Instruction::Adv => format!("a = a >> {}", combo_name(operand)),
+                Instruction::Bxl => format!("b = b ^ {operand}"),
+                Instruction::Bst => format!("b = {} % 8", combo_name(operand)),
+                Instruction::Jnz => format!("if a != 0 jump {operand}"),
+                Instruction::Bxc => "b = b ^ c".to_string(),
+                Instruction::Out => format!("out {} % 8", combo_name(operand)),
+                Instruction::Bdv => format!("b = a >> {}", combo_name(operand)),
+                Instruction::Cdv => format!("c = a >> {}", combo_name(operand)),
+            };
+            format!("{:>3}: {code}", index * 2)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The registers and output after every instruction, with the opcode and operand that ran
+fn trace(mut computer: Computer) -> String {
+    let mut lines = Vec::new();
+    while !computer.is_halted() {
+        let pointer = computer.pointer();
+        let (opcode, operand) = (computer.program()[pointer], computer.program()[pointer + 1]);
+        computer.step();
+        let Registers { a, b, c } = computer.registers();
+        lines.push(format!(
+            "{pointer:>3}: {:?} {operand}  A={a} B={b} C={c}  out={:?}",
+            Instruction::from(opcode),
+            computer.output()
+        ));
+    }
+    lines.join("\n")
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct Registers {
+    pub a: u64,
+    pub b: u64,
+    pub c: u64,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Instruction {
+    Adv,
+    Bxl,
+    Bst,
+    Jnz,
+    Bxc,
+    Out,
+    Bdv,
+    Cdv,
+}
+
+impl From<u8> for Instruction {
+    /// Only the low three bits count, so every value is some instruction
+    fn from(opcode: u8) -> Self {
+        match opcode & 0b111 {
+            0 => Instruction::Adv,
+            1 => Instruction::Bxl,
+            2 => Instruction::Bst,
+            3 => Instruction::Jnz,
+            4 => Instruction::Bxc,
+            5 => Instruction::Out,
+            6 => Instruction::Bdv,
+            _ => Instruction::Cdv,
+        }
+    }
+}
+
+/// The Chronospatial Computer, which can be run to completion or stepped one instruction at a time
+#[derive(Clone, Debug)]
+pub struct Computer {
+    registers: Registers,
+    program: Vec<u8>,
+    pointer: usize,
+    output: Vec<u8>,
+}
+
+impl Computer {
+    pub fn new(registers: Registers, program: Vec<u8>) -> Self {
+        Computer {
+            registers,
+            program,
+            pointer: 0,
+            output: Vec::new(),
+        }
+    }
+
+    pub fn registers(&self) -> Registers {
+        self.registers
+    }
+
+    pub fn program(&self) -> &[u8] {
+        &self.program
+    }
+
+    pub fn pointer(&self) -> usize {
+        self.pointer
+    }
+
+    pub fn output(&self) -> &[u8] {
+        &self.output
+    }
+
+    /// Reading an opcode or its operand past the end of the program halts
+    pub fn is_halted(&self) -> bool {
+        self.pointer + 1 >= self.program.len()
+    }
+
+    fn combo(&self, operand: u8) -> u64 {
+        match operand {
+            0..=3 => operand as u64,
+            4 => self.registers.a,
+            5 => self.registers.b,
+            6 => self.registers.c,
+            _ => panic!("Combo operand {operand} is reserved"),
+        }
+    }
+
+    /// The division instructions, where a large enough power of two just gives zero
+    fn divide(&self, operand: u8) -> u64 {
+        u32::try_from(self.combo(operand))
+            .ok()
+            .and_then(|shift| self.registers.a.checked_shr(shift))
+            .unwrap_or(0)
+    }
+
+    /// Runs a single instruction, returning false if the computer had already halted
+    pub fn step(&mut self) -> bool {
+        if self.is_halted() {
+            return false;
+        }
+        let instruction = Instruction::from(self.program[self.pointer]);
+        let operand = self.program[self.pointer + 1];
+        self.pointer += 2;
+
+        match instruction {
+            Instruction::Adv => self.registers.a = self.divide(operand),
+            Instruction::Bxl => self.registers.b ^= operand as u64,
+            Instruction::Bst => self.registers.b = self.combo(operand) % 8,
+            Instruction::Jnz => {
+                if self.registers.a != 0 {
+                    self.pointer = operand as usize;
+                }
+            }
+            Instruction::Bxc => self.registers.b ^= self.registers.c,
+            Instruction::Out => self.output.push((self.combo(operand) % 8) as u8),
+            Instruction::Bdv => self.registers.b = self.divide(operand),
+            Instruction::Cdv => self.registers.c = self.divide(operand),
+        }
+        true
+    }
+
+    /// Steps until the computer halts, returning everything output along the way
+    pub fn run(&mut self) -> &[u8] {
+        while self.step() {}
+        &self.output
+    }
+}
+
+fn parse_register(name: char) -> impl FnMut(&str) -> IResult<&str, u64, ErrorTree<&str>> {
+    move |input| {
+        delimited(
+            tuple((
+                tag("Register "),
+                one_of(name.to_string().as_str()),
+                tag(": "),
+            )),
+            digit1.map_res(str::parse),
+            line_ending,
+        )(input)
+    }
+}
+
+fn parse_registers(input: &str) -> IResult<&str, Registers, ErrorTree<&str>> {
+    tuple((
+        parse_register('A'),
+        parse_register('B'),
+        parse_register('C'),
+    ))(input)
+    .map(|(remainder, (a, b, c))| (remainder, Registers { a, b, c }))
+}
+
+fn parse_program(input: &str) -> IResult<&str, Vec<u8>, ErrorTree<&str>> {
+    preceded(
+        tag("Program: "),
+        separated_list1(tag(","), one_of("01234567").map(|c| c as u8 - b'0')),
+    )(input)
+}
+
+fn parse_computer(input: &str) -> IResult<&str, Computer, ErrorTree<&str>> {
+    tuple((parse_registers, preceded(line_ending, parse_program)))(input)
+        .map(|(remainder, (registers, program))| (remainder, Computer::new(registers, program)))
+}
+
+fn get_computer(input: &str) -> Result<Computer, ErrorTree<&str>> {
+    final_parser(parse_computer.terminated(line_ending.opt()).all_consuming())(input)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    #[ignore]
+    fn run(registers: Registers, program: &[u8]) -> Computer {
+        let mut computer = Computer::new(registers, program.to_vec());
+        computer.run();
+        computer
+    }
+
+    #[test]
+    fn test_bst() {
+        let computer = run(
+            Registers {
+                c: 9,
+                ..Default::default()
+            },
+            &[2, 6],
+        );
+        assert_eq!(computer.registers().b, 1);
+    }
+
+    #[test]
+    fn test_out() {
+        let computer = run(
+            Registers {
+                a: 10,
+                ..Default::default()
+            },
+            &[5, 0, 5, 1, 5, 4],
+        );
+        assert_eq!(computer.output(), [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_loop() {
+        let computer = run(
+            Registers {
+                a: 2024,
+                ..Default::default()
+            },
+            &[0, 1, 5, 4, 3, 0],
+        );
+        assert_eq!(computer.output(), [4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0]);
+        assert_eq!(computer.registers().a, 0);
+    }
+
+    #[test]
+    fn test_bxl() {
+        let computer = run(
+            Registers {
+                b: 29,
+                ..Default::default()
+            },
+            &[1, 7],
+        );
+        assert_eq!(computer.registers().b, 26);
+    }
+
+    #[test]
+    fn test_bxc() {
+        let computer = run(
+            Registers {
+                b: 2024,
+                c: 43690,
+                ..Default::default()
+            },
+            &[4, 0],
+        );
+        assert_eq!(computer.registers().b, 44354);
+    }
+
+    #[test]
+    fn test_step() {
+        let mut computer = Computer::new(
+            Registers {
+                a: 10,
+                ..Default::default()
+            },
+            vec![5, 0, 5, 1],
+        );
+        assert!(computer.step());
+        assert_eq!(computer.pointer(), 2);
+        assert_eq!(computer.output(), [0]);
+        assert!(computer.step());
+        assert!(computer.is_halted());
+        assert!(!computer.step());
+        assert_eq!(computer.output(), [0, 1]);
+    }
+
+    #[test]
+    fn test_trace() {
+        let computer = Computer::new(
+            Registers {
+                a: 2,
+                ..Default::default()
+            },
+            vec![0, 1, 5, 4],
+        );
+        assert_eq!(
+            trace(computer),
+            "  0: Adv 1  A=1 B=0 C=0  out=[]\n  2: Out 4  A=1 B=0 C=0  out=[1]"
+        );
+    }
+
     #[test]
     fn test_part1() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part1(input), "");
+        let input = "Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0
+";
+        assert_eq!(AdventPuzzle::part1(input), "4,6,3,5,6,3,5,2,1,0");
+    }
+
+    #[test]
+    fn test_disassemble() {
+        assert_eq!(
+            disassemble(&[2, 4, 1, 3, 7, 5, 4, 7, 0, 3, 5, 5, 3, 0]),
+            "  0: b = a % 8
+  2: b = b ^ 3
+  4: c = a >> b
+  6: b = b ^ c
+  8: a = a >> 3
+ 10: out b % 8
+ 12: if a != 0 jump 0"
+        );
     }
 
-    #[ignore]
     #[test]
     fn test_part2() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part2(input), "");
+        let input = "Register A: 2024
+Register B: 0
+Register C: 0
+
+Program: 0,3,5,4,3,0
+";
+        assert_eq!(AdventPuzzle::part2(input), "117440");
     }
 }
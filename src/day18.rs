@@ -1,32 +1,290 @@
 use crate::solution::Solution;
+use nom::character::complete::{digit1, line_ending};
+use nom::multi::separated_list1;
+use nom::sequence::separated_pair;
+use nom::{IResult, Parser};
+use nom_supreme::error::ErrorTree;
+use nom_supreme::final_parser::final_parser;
+use nom_supreme::tag::complete::tag;
+use nom_supreme::ParserExt;
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
 
 pub struct AdventPuzzle;
 
 impl Solution for AdventPuzzle {
-    fn part1(_input: &str) -> String {
-        todo!()
+    fn part1(input: &str) -> String {
+        steps_to_exit(input, &MEMORY_SPACE).to_string()
     }
 
-    fn part2(_input: &str) -> String {
-        todo!()
+    fn part2(input: &str) -> String {
+        first_blocking_byte(input, &MEMORY_SPACE).to_string()
     }
 }
 
+const MEMORY_SPACE: MemorySpace = MemorySpace {
+    size: 71,
+    fallen: 1024,
+};
+
+/// Fewest steps to the exit, in a memory space of the given size
+fn steps_to_exit(input: &str, space: &MemorySpace) -> usize {
+    let bytes = get_bytes(input).expect("Could not parse bytes");
+    space
+        .shortest_path(&bytes)
+        .expect("The exit should be reachable")
+}
+
+/// The first byte that cuts off the exit, in a memory space of the given size
+fn first_blocking_byte(input: &str, space: &MemorySpace) -> Byte {
+    let bytes = get_bytes(input).expect("Could not parse bytes");
+    space
+        .first_blocking_byte(&bytes)
+        .expect("Some byte should cut off the exit")
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+struct Byte {
+    x: usize,
+    y: usize,
+}
+
+impl Display for Byte {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+/// A square grid from 0,0 to the exit in the far corner, and how many bytes have fallen into it
+/// by the time we start walking
+struct MemorySpace {
+    size: usize,
+    fallen: usize,
+}
+
+impl MemorySpace {
+    fn index(&self, Byte { x, y }: Byte) -> usize {
+        y * self.size + x
+    }
+
+    fn neighbours(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        let (x, y) = (index % self.size, index / self.size);
+        [
+            (x > 0).then(|| index - 1),
+            (x + 1 < self.size).then(|| index + 1),
+            (y > 0).then(|| index - self.size),
+            (y + 1 < self.size).then(|| index + self.size),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    fn corrupted(&self, bytes: &[Byte]) -> Vec<bool> {
+        let mut corrupted = vec![false; self.size * self.size];
+        for byte in bytes {
+            corrupted[self.index(*byte)] = true;
+        }
+        corrupted
+    }
+
+    /// Fewest steps from the top left to the exit once the first bytes have fallen
+    fn shortest_path(&self, bytes: &[Byte]) -> Option<usize> {
+        let corrupted = self.corrupted(&bytes[..self.fallen.min(bytes.len())]);
+        let exit = self.size * self.size - 1;
+        let mut steps = vec![None; self.size * self.size];
+        let mut queue = VecDeque::new();
+        if !corrupted[0] {
+            steps[0] = Some(0);
+            queue.push_back(0);
+        }
+
+        while let Some(index) = queue.pop_front() {
+            let distance = steps[index]?;
+            if index == exit {
+                return Some(distance);
+            }
+            for next in self.neighbours(index) {
+                if !corrupted[next] && steps[next].is_none() {
+                    steps[next] = Some(distance + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// The first byte after which the exit can't be reached.
+    ///
+    /// Rather than searching again after every byte, let them all fall and then lift them back out
+    /// in reverse, joining up the free cells as we go. The byte whose removal first connects the
+    /// start to the exit is the one that cut it off.
+    fn first_blocking_byte(&self, bytes: &[Byte]) -> Option<Byte> {
+        let mut corrupted = self.corrupted(bytes);
+        let mut cells = DisjointSet::new(self.size * self.size);
+        let exit = self.size * self.size - 1;
+
+        for index in 0..corrupted.len() {
+            if !corrupted[index] {
+                for next in self.neighbours(index) {
+                    if !corrupted[next] {
+                        cells.union(index, next);
+                    }
+                }
+            }
+        }
+        if cells.find(0) == cells.find(exit) {
+            return None;
+        }
+
+        bytes.iter().rev().copied().find(|&byte| {
+            let index = self.index(byte);
+            corrupted[index] = false;
+            for next in self.neighbours(index) {
+                if !corrupted[next] {
+                    cells.union(index, next);
+                }
+            }
+            cells.find(0) == cells.find(exit)
+        })
+    }
+}
+
+/// Union-find with path compression and union by size
+struct DisjointSet {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(size: usize) -> Self {
+        DisjointSet {
+            parents: (0..size).collect(),
+            sizes: vec![1; size],
+        }
+    }
+
+    fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        let mut current = element;
+        while self.parents[current] != root {
+            current = std::mem::replace(&mut self.parents[current], root);
+        }
+        root
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        let (larger, smaller) = if self.sizes[a] >= self.sizes[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parents[smaller] = larger;
+        self.sizes[larger] += self.sizes[smaller];
+    }
+}
+
+fn parse_number(input: &str) -> IResult<&str, usize, ErrorTree<&str>> {
+    digit1.map_res(str::parse).parse(input)
+}
+
+fn parse_byte(input: &str) -> IResult<&str, Byte, ErrorTree<&str>> {
+    separated_pair(parse_number, tag(","), parse_number)(input)
+        .map(|(remainder, (x, y))| (remainder, Byte { x, y }))
+}
+
+fn parse_bytes(input: &str) -> IResult<&str, Vec<Byte>, ErrorTree<&str>> {
+    separated_list1(line_ending, parse_byte)(input)
+}
+
+fn get_bytes(input: &str) -> Result<Vec<Byte>, ErrorTree<&str>> {
+    final_parser(parse_bytes.terminated(line_ending.opt()).all_consuming())(input)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
-    #[ignore]
+    const EXAMPLE_MEMORY_SPACE: MemorySpace = MemorySpace {
+        size: 7,
+        fallen: 12,
+    };
+
+    const EXAMPLE: &str = "5,4
+4,2
+4,5
+3,0
+2,1
+6,3
+2,4
+1,5
+0,6
+3,3
+2,6
+5,1
+1,2
+5,5
+2,5
+6,5
+1,4
+0,4
+6,4
+1,1
+6,1
+1,0
+0,5
+1,6
+2,0
+";
+
+    #[test]
+    fn test_shortest_path() {
+        let bytes = get_bytes(EXAMPLE).unwrap();
+        assert_eq!(EXAMPLE_MEMORY_SPACE.shortest_path(&bytes), Some(22));
+    }
+
+    #[test]
+    fn test_first_blocking_byte() {
+        let bytes = get_bytes(EXAMPLE).unwrap();
+        assert_eq!(
+            EXAMPLE_MEMORY_SPACE.first_blocking_byte(&bytes),
+            Some(Byte { x: 6, y: 1 })
+        );
+        assert_eq!(EXAMPLE_MEMORY_SPACE.first_blocking_byte(&bytes[..20]), None);
+    }
+
+    #[test]
+    fn test_blocking_byte_agrees_with_shortest_path() {
+        let bytes = get_bytes(EXAMPLE).unwrap();
+        let blocked_after = (1..=bytes.len())
+            .find(|&fallen| {
+                MemorySpace { size: 7, fallen }
+                    .shortest_path(&bytes)
+                    .is_none()
+            })
+            .unwrap();
+        assert_eq!(
+            EXAMPLE_MEMORY_SPACE.first_blocking_byte(&bytes),
+            Some(bytes[blocked_after - 1])
+        );
+    }
+
     #[test]
     fn test_part1() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part1(input), "");
+        assert_eq!(steps_to_exit(EXAMPLE, &EXAMPLE_MEMORY_SPACE), 22);
     }
 
-    #[ignore]
     #[test]
     fn test_part2() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part2(input), "");
+        assert_eq!(
+            first_blocking_byte(EXAMPLE, &EXAMPLE_MEMORY_SPACE).to_string(),
+            "6,1"
+        );
     }
 }
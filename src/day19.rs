@@ -1,32 +1,199 @@
 use crate::solution::Solution;
+use nom::bytes::complete::is_a;
+use nom::character::complete::line_ending;
+use nom::multi::{count, separated_list1};
+use nom::sequence::separated_pair;
+use nom::{IResult, Parser};
+use nom_supreme::error::ErrorTree;
+use nom_supreme::final_parser::final_parser;
+use nom_supreme::tag::complete::tag;
+use nom_supreme::ParserExt;
 
 pub struct AdventPuzzle;
 
 impl Solution for AdventPuzzle {
-    fn part1(_input: &str) -> String {
-        todo!()
+    fn part1(input: &str) -> String {
+        let (towels, designs) = get_onsen(input).expect("Could not parse towels and designs");
+        designs
+            .iter()
+            .filter(|design| towels.arrangements(design) > 0)
+            .count()
+            .to_string()
     }
 
-    fn part2(_input: &str) -> String {
-        todo!()
+    fn part2(input: &str) -> String {
+        let (towels, designs) = get_onsen(input).expect("Could not parse towels and designs");
+        designs
+            .iter()
+            .map(|design| towels.arrangements(design))
+            .sum::<u64>()
+            .to_string()
     }
+
+    fn explain_part1(input: &str) -> Option<String> {
+        let (towels, designs) = get_onsen(input).ok()?;
+        let impossible = impossible_designs(&towels, &designs);
+        Some(format!(
+            "{} of {} designs are impossible:\n{}",
+            impossible.len(),
+            designs.len(),
+            impossible.join("\n")
+        ))
+    }
+}
+
+/// The stripe colours, in the order the trie stores children
+const COLOURS: &str = "wubrg";
+
+#[derive(Default, Debug)]
+struct TrieNode {
+    children: [Option<usize>; COLOURS.len()],
+    is_towel: bool,
+}
+
+/// Every towel pattern in a trie, so matching all the towels at a position in a design is one
+/// walk down from the root rather than a comparison per towel.
+#[derive(Debug)]
+struct Towels {
+    nodes: Vec<TrieNode>,
+}
+
+impl Towels {
+    fn new<'a>(patterns: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut towels = Towels {
+            nodes: vec![TrieNode::default()],
+        };
+        for pattern in patterns {
+            towels.insert(pattern);
+        }
+        towels
+    }
+
+    fn colour_index(stripe: u8) -> Option<usize> {
+        COLOURS.bytes().position(|colour| colour == stripe)
+    }
+
+    fn insert(&mut self, pattern: &str) {
+        let mut node = 0;
+        for stripe in pattern.bytes() {
+            let colour =
+                Self::colour_index(stripe).expect("Stripe colours are checked when parsing");
+            node = match self.nodes[node].children[colour] {
+                Some(child) => child,
+                None => {
+                    self.nodes.push(TrieNode::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children[colour] = Some(child);
+                    child
+                }
+            };
+        }
+        self.nodes[node].is_towel = true;
+    }
+
+    /// The lengths of every towel that matches the start of the stripes
+    fn prefix_lengths<'a>(&'a self, stripes: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        stripes
+            .iter()
+            .scan(0, |node, &stripe| {
+                *node = self.nodes[*node].children[Self::colour_index(stripe)?]?;
+                Some(*node)
+            })
+            .enumerate()
+            .filter(|(_, node)| self.nodes[*node].is_towel)
+            .map(|(index, _)| index + 1)
+    }
+
+    /// How many different ways the towels can be lined up to make the design.
+    ///
+    /// Works backwards so the count for each suffix of the design is ready by the time any
+    /// towel ending there needs it.
+    fn arrangements(&self, design: &str) -> u64 {
+        let stripes = design.as_bytes();
+        let mut ways = vec![0; stripes.len() + 1];
+        ways[stripes.len()] = 1;
+        for start in (0..stripes.len()).rev() {
+            ways[start] = self
+                .prefix_lengths(&stripes[start..])
+                .map(|length| ways[start + length])
+                .sum();
+        }
+        ways[0]
+    }
+}
+
+fn impossible_designs<'a>(towels: &Towels, designs: &[&'a str]) -> Vec<&'a str> {
+    designs
+        .iter()
+        .copied()
+        .filter(|design| towels.arrangements(design) == 0)
+        .collect()
+}
+
+fn parse_stripes(input: &str) -> IResult<&str, &str, ErrorTree<&str>> {
+    is_a(COLOURS)
+        .context("Stripes should be white, blue, black, red or green")
+        .parse(input)
+}
+
+fn parse_onsen(input: &str) -> IResult<&str, (Towels, Vec<&str>), ErrorTree<&str>> {
+    separated_pair(
+        separated_list1(tag(", "), parse_stripes),
+        count(line_ending, 2),
+        separated_list1(line_ending, parse_stripes),
+    )(input)
+    .map(|(remainder, (patterns, designs))| (remainder, (Towels::new(patterns), designs)))
+}
+
+fn get_onsen(input: &str) -> Result<(Towels, Vec<&str>), ErrorTree<&str>> {
+    final_parser(parse_onsen.terminated(line_ending.opt()).all_consuming())(input)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    #[ignore]
+    const EXAMPLE: &str = "r, wr, b, g, bwu, rb, gb, br
+
+brwrr
+bggr
+gbbr
+rrbgbr
+ubwu
+bwurrg
+brgr
+bbrgwb
+";
+
+    #[test]
+    fn test_arrangements() {
+        let (towels, designs) = get_onsen(EXAMPLE).unwrap();
+        let arrangements: Vec<u64> = designs
+            .iter()
+            .map(|design| towels.arrangements(design))
+            .collect();
+        assert_eq!(arrangements, [2, 1, 4, 6, 0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn test_impossible_designs() {
+        let (towels, designs) = get_onsen(EXAMPLE).unwrap();
+        assert_eq!(impossible_designs(&towels, &designs), ["ubwu", "bbrgwb"]);
+    }
+
+    #[test]
+    fn test_unknown_colour() {
+        assert!(get_onsen("r, wx, b\n\nrb\n").is_err());
+        assert!(get_onsen("r, b\n\nrxb\n").is_err());
+    }
+
     #[test]
     fn test_part1() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part1(input), "");
+        assert_eq!(AdventPuzzle::part1(EXAMPLE), "6");
     }
 
-    #[ignore]
     #[test]
     fn test_part2() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part2(input), "");
+        assert_eq!(AdventPuzzle::part2(EXAMPLE), "16");
     }
 }
@@ -1,14 +1,196 @@
 use crate::solution::Solution;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 pub struct AdventPuzzle;
 
 impl Solution for AdventPuzzle {
-    fn part1(_input: &str) -> String {
-        todo!()
+    fn part1(input: &str) -> String {
+        let track = RaceTrack::from_str(input).expect("Invalid race track");
+        track.count_cheats(SHORT_CHEATS).to_string()
     }
 
-    fn part2(_input: &str) -> String {
-        todo!()
+    fn part2(input: &str) -> String {
+        let track = RaceTrack::from_str(input).expect("Invalid race track");
+        track.count_cheats(LONG_CHEATS).to_string()
+    }
+}
+
+const SHORT_CHEATS: CheatRules = CheatRules {
+    max_length: 2,
+    min_saving: 100,
+};
+
+const LONG_CHEATS: CheatRules = CheatRules {
+    max_length: 20,
+    min_saving: 100,
+};
+
+/// How long a cheat may last and how much time it has to save to be worth counting
+#[derive(Copy, Clone, Debug)]
+struct CheatRules {
+    max_length: usize,
+    min_saving: usize,
+}
+
+#[derive(Debug)]
+struct RaceTrack {
+    walls: Vec<Vec<bool>>,
+    start: (usize, usize),
+    end: (usize, usize),
+}
+
+impl RaceTrack {
+    fn neighbours(&self, (row, column): (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        [
+            row.checked_sub(1).map(|row| (row, column)),
+            Some((row + 1, column)),
+            column.checked_sub(1).map(|column| (row, column)),
+            Some((row, column + 1)),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// Picoseconds from the given position to every track position, without cheating
+    fn distances_from(&self, from: (usize, usize)) -> Vec<Vec<Option<usize>>> {
+        let mut distances = vec![vec![None; self.walls[0].len()]; self.walls.len()];
+        distances[from.0][from.1] = Some(0);
+        let mut queue = VecDeque::from([(from, 0)]);
+
+        while let Some((position, distance)) = queue.pop_front() {
+            for (row, column) in self.neighbours(position) {
+                let open = self
+                    .walls
+                    .get(row)
+                    .and_then(|walls| walls.get(column))
+                    .is_some_and(|&wall| !wall);
+                if open && distances[row][column].is_none() {
+                    distances[row][column] = Some(distance + 1);
+                    queue.push_back(((row, column), distance + 1));
+                }
+            }
+        }
+        distances
+    }
+
+    /// Cheats that save at least the minimum, each counted once by where it starts and ends.
+    ///
+    /// A cheat from one track position to another any Manhattan distance within the limit takes
+    /// that many picoseconds, so with the distances from the start and to the end known its
+    /// saving is just arithmetic.
+    fn count_cheats(&self, rules: CheatRules) -> usize {
+        let from_start = self.distances_from(self.start);
+        let to_end = self.distances_from(self.end);
+        let Some(fair) = from_start[self.end.0][self.end.1] else {
+            return 0;
+        };
+        let limit = rules.max_length as isize;
+
+        let mut cheats = 0;
+        for (row, distances) in from_start.iter().enumerate() {
+            for (column, distance) in distances.iter().enumerate() {
+                let Some(before) = distance else {
+                    continue;
+                };
+                for row_offset in -limit..=limit {
+                    let remaining = limit - row_offset.abs();
+                    for column_offset in -remaining..=remaining {
+                        let target = (
+                            row.checked_add_signed(row_offset),
+                            column.checked_add_signed(column_offset),
+                        );
+                        let after = match target {
+                            (Some(row), Some(column)) => to_end
+                                .get(row)
+                                .and_then(|distances| distances.get(column))
+                                .copied()
+                                .flatten(),
+                            _ => None,
+                        };
+                        let Some(after) = after else {
+                            continue;
+                        };
+                        let length = (row_offset.abs() + column_offset.abs()) as usize;
+                        if length < 2 {
+                            // Staying put or stepping to a neighbour is just racing
+                            continue;
+                        }
+                        let time = before + length + after;
+                        if time + rules.min_saving <= fair {
+                            cheats += 1;
+                        }
+                    }
+                }
+            }
+        }
+        cheats
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum RaceTrackError {
+    InvalidTile(char),
+    MissingStart,
+    TooManyStarts,
+    MissingEnd,
+    TooManyEnds,
+}
+
+impl Display for RaceTrackError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RaceTrackError::InvalidTile(c) => write!(f, "invalid tile {c:?}"),
+            RaceTrackError::MissingStart => write!(f, "missing start"),
+            RaceTrackError::TooManyStarts => write!(f, "more than one start"),
+            RaceTrackError::MissingEnd => write!(f, "missing end"),
+            RaceTrackError::TooManyEnds => write!(f, "more than one end"),
+        }
+    }
+}
+
+impl Error for RaceTrackError {}
+
+impl FromStr for RaceTrack {
+    type Err = RaceTrackError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut start = None;
+        let mut end = None;
+        let walls = s
+            .lines()
+            .enumerate()
+            .map(|(row, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(column, c)| match c {
+                        '#' => Ok(true),
+                        '.' => Ok(false),
+                        'S' => {
+                            if start.replace((row, column)).is_some() {
+                                return Err(RaceTrackError::TooManyStarts);
+                            }
+                            Ok(false)
+                        }
+                        'E' => {
+                            if end.replace((row, column)).is_some() {
+                                return Err(RaceTrackError::TooManyEnds);
+                            }
+                            Ok(false)
+                        }
+                        c => Err(RaceTrackError::InvalidTile(c)),
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(RaceTrack {
+            walls,
+            start: start.ok_or(RaceTrackError::MissingStart)?,
+            end: end.ok_or(RaceTrackError::MissingEnd)?,
+        })
     }
 }
 
@@ -16,17 +198,97 @@ impl Solution for AdventPuzzle {
 mod test {
     use super::*;
 
-    #[ignore]
+    const EXAMPLE: &str = "###############
+#...#...#.....#
+#.#.#.#.#.###.#
+#S#...#.#.#...#
+#######.#.#.###
+#######.#.#...#
+#######.#.###.#
+###..E#...#...#
+###.#######.###
+#...###...#...#
+#.#####.#.###.#
+#.#...#.#.#...#
+#.#.#.#.#.#.###
+#...#...#...###
+###############
+";
+
+    fn cheats(max_length: usize, min_saving: usize) -> usize {
+        RaceTrack::from_str(EXAMPLE)
+            .unwrap()
+            .count_cheats(CheatRules {
+                max_length,
+                min_saving,
+            })
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            RaceTrack::from_str("#####\n#S..#\n#####").unwrap_err(),
+            RaceTrackError::MissingEnd
+        );
+        assert_eq!(
+            RaceTrack::from_str("#####\n#..E#\n#####").unwrap_err(),
+            RaceTrackError::MissingStart
+        );
+        assert_eq!(
+            RaceTrack::from_str("#####\n#SEE#\n#####").unwrap_err(),
+            RaceTrackError::TooManyEnds
+        );
+        assert_eq!(
+            RaceTrack::from_str("#####\n#S?E#\n#####").unwrap_err(),
+            RaceTrackError::InvalidTile('?')
+        );
+    }
+
+    #[test]
+    fn test_short_cheats() {
+        assert_eq!(cheats(2, 64), 1);
+        assert_eq!(cheats(2, 40), 2);
+        assert_eq!(cheats(2, 20), 5);
+        assert_eq!(cheats(2, 1), 44);
+    }
+
+    #[test]
+    fn test_long_cheats() {
+        assert_eq!(cheats(20, 76), 3);
+        assert_eq!(cheats(20, 74), 7);
+        assert_eq!(cheats(20, 50), 285);
+    }
+
+    #[test]
+    fn test_no_cheat_without_skipping() {
+        let track = RaceTrack::from_str("###\n#S#\n#E#\n###").unwrap();
+        assert_eq!(
+            track.count_cheats(CheatRules {
+                max_length: 2,
+                min_saving: 0,
+            }),
+            0
+        );
+    }
+
     #[test]
     fn test_part1() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part1(input), "");
+        // Nothing in the example saves 100, so check the part's cheat length with a lower bar
+        let track = RaceTrack::from_str(EXAMPLE).unwrap();
+        let rules = CheatRules {
+            min_saving: 64,
+            ..SHORT_CHEATS
+        };
+        assert_eq!(track.count_cheats(rules), 1);
     }
 
-    #[ignore]
     #[test]
     fn test_part2() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part2(input), "");
+        let track = RaceTrack::from_str(EXAMPLE).unwrap();
+        let rules = CheatRules {
+            min_saving: 76,
+            ..LONG_CHEATS
+        };
+        assert_eq!(track.count_cheats(rules), 3);
     }
 }
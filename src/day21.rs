@@ -1,32 +1,179 @@
 use crate::solution::Solution;
+use std::collections::HashMap;
 
 pub struct AdventPuzzle;
 
 impl Solution for AdventPuzzle {
-    fn part1(_input: &str) -> String {
-        todo!()
+    fn part1(input: &str) -> String {
+        complexity_sum(input, 2).to_string()
     }
 
-    fn part2(_input: &str) -> String {
-        todo!()
+    fn part2(input: &str) -> String {
+        complexity_sum(input, 25).to_string()
     }
 }
 
+/// Rows of keys, with a space where the gap is that no robot arm may point at
+struct Keypad {
+    rows: &'static [&'static str],
+}
+
+const NUMERIC: Keypad = Keypad {
+    rows: &["789", "456", "123", " 0A"],
+};
+
+const DIRECTIONAL: Keypad = Keypad {
+    rows: &[" ^A", "<v>"],
+};
+
+impl Keypad {
+    fn position(&self, key: char) -> (usize, usize) {
+        self.rows
+            .iter()
+            .enumerate()
+            .find_map(|(row, keys)| keys.chars().position(|k| k == key).map(|c| (row, c)))
+            .unwrap_or_else(|| panic!("No key {key} on this keypad"))
+    }
+
+    /// Directional presses that move an arm from one key to the other and then push it.
+    ///
+    /// Zigzagging is never cheaper further up the chain, so only the two L-shaped routes are worth
+    /// trying, and not the ones that would sweep over the gap.
+    fn moves(&self, from: char, to: char) -> Vec<String> {
+        let (from_row, from_column) = self.position(from);
+        let (to_row, to_column) = self.position(to);
+        let (gap_row, gap_column) = self.position(' ');
+
+        let vertical = if to_row > from_row { "v" } else { "^" }.repeat(from_row.abs_diff(to_row));
+        let horizontal =
+            if to_column > from_column { ">" } else { "<" }.repeat(from_column.abs_diff(to_column));
+
+        let mut moves = Vec::new();
+        if (from_row, to_column) != (gap_row, gap_column) {
+            moves.push(format!("{horizontal}{vertical}A"));
+        }
+        if (to_row, from_column) != (gap_row, gap_column) {
+            moves.push(format!("{vertical}{horizontal}A"));
+        }
+        moves.dedup();
+        moves
+    }
+}
+
+/// Directional keypad robots stacked between us and the robot at the door's numeric keypad
+struct KeypadChain {
+    robots: usize,
+    costs: HashMap<(char, char, usize), u64>,
+}
+
+impl KeypadChain {
+    fn new(robots: usize) -> Self {
+        KeypadChain {
+            robots,
+            costs: HashMap::new(),
+        }
+    }
+
+    /// Human presses needed to type the keys on a directional keypad with this many robots still
+    /// between it and us, every arm starting on `A`
+    fn sequence_cost(&mut self, keys: &str, depth: usize) -> u64 {
+        let mut from = 'A';
+        keys.chars()
+            .map(|to| {
+                let cost = self.press_cost(from, to, depth);
+                from = to;
+                cost
+            })
+            .sum()
+    }
+
+    /// Once pushed a key leaves every arm above it back on `A`, so the cost only depends on which
+    /// two keys and how deep we are
+    fn press_cost(&mut self, from: char, to: char, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        if let Some(&cost) = self.costs.get(&(from, to, depth)) {
+            return cost;
+        }
+        let cost = DIRECTIONAL
+            .moves(from, to)
+            .iter()
+            .map(|keys| self.sequence_cost(keys, depth - 1))
+            .min()
+            .expect("There should always be a way between two keys");
+        self.costs.insert((from, to, depth), cost);
+        cost
+    }
+
+    /// Fewest human presses to get the door robot to type the code
+    fn code_cost(&mut self, code: &str) -> u64 {
+        let mut from = 'A';
+        code.chars()
+            .map(|to| {
+                let cost = NUMERIC
+                    .moves(from, to)
+                    .iter()
+                    .map(|keys| self.sequence_cost(keys, self.robots))
+                    .min()
+                    .expect("There should always be a way between two keys");
+                from = to;
+                cost
+            })
+            .sum()
+    }
+
+    fn complexity(&mut self, code: &str) -> u64 {
+        let numeric: u64 = code
+            .trim_end_matches('A')
+            .parse()
+            .expect("Codes should be a number followed by A");
+        self.code_cost(code) * numeric
+    }
+}
+
+fn complexity_sum(input: &str, robots: usize) -> u64 {
+    let mut chain = KeypadChain::new(robots);
+    input
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|code| chain.complexity(code))
+        .sum()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
-    #[ignore]
+    const EXAMPLE: &str = "029A
+980A
+179A
+456A
+379A
+";
+
+    #[test]
+    fn test_moves_avoid_gap() {
+        assert_eq!(NUMERIC.moves('A', '1'), ["^<<A"]);
+        assert_eq!(NUMERIC.moves('7', '0'), [">vvvA"]);
+        assert_eq!(DIRECTIONAL.moves('<', 'A'), [">>^A"]);
+        assert_eq!(DIRECTIONAL.moves('A', 'A'), ["A"]);
+    }
+
+    #[test]
+    fn test_code_cost() {
+        let mut chain = KeypadChain::new(2);
+        let costs: Vec<u64> = EXAMPLE.lines().map(|code| chain.code_cost(code)).collect();
+        assert_eq!(costs, [68, 60, 68, 64, 64]);
+    }
+
     #[test]
     fn test_part1() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part1(input), "");
+        assert_eq!(AdventPuzzle::part1(EXAMPLE), "126384");
     }
 
-    #[ignore]
     #[test]
     fn test_part2() {
-        let input = "";
-        assert_eq!(AdventPuzzle::part2(input), "");
+        assert_eq!(AdventPuzzle::part2(EXAMPLE), "154115708116294");
     }
 }
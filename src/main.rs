@@ -40,6 +40,14 @@ struct Opt {
     day: usize,
     #[structopt(short = "p", long = "part")]
     part: usize,
+    #[structopt(short = "v", long = "verbose")]
+    verbose: bool,
+    /// How many times to blink on day 11, overriding the part's own count
+    #[structopt(short = "b", long = "blinks")]
+    blinks: Option<usize>,
+    /// Print a Graphviz DOT graph of the puzzle instead of solving it, optionally for one item
+    #[structopt(long = "dot")]
+    dot: Option<Option<usize>>,
 }
 
 fn main() {
@@ -52,70 +60,137 @@ fn main() {
         Err(_) => panic!("Input not found: {input_file}"),
     };
 
+    if let Some(item) = opt.dot {
+        match opt.day {
+            5 => match day05::render_dot(&input, item) {
+                Ok(dot) => println!("{dot}"),
+                Err(error) => {
+                    eprintln!("{error}");
+                    exit(1);
+                }
+            },
+            _ => {
+                eprintln!("Day {} has no graph output", opt.day);
+                exit(1);
+            }
+        }
+        return;
+    }
+
+    if opt.blinks.is_some() && opt.day != 11 {
+        eprintln!("Only day 11 takes a blink count");
+        exit(1);
+    }
+
     let start = Instant::now();
-    let result = match (opt.day, opt.part) {
-        (1, 1) => day01::AdventPuzzle::part1(&input),
-        (1, 2) => day01::AdventPuzzle::part2(&input),
-        (2, 1) => day02::AdventPuzzle::part1(&input),
-        (2, 2) => day02::AdventPuzzle::part2(&input),
-        (3, 1) => day03::AdventPuzzle::part1(&input),
-        (3, 2) => day03::AdventPuzzle::part2(&input),
-        (4, 1) => day04::AdventPuzzle::part1(&input),
-        (4, 2) => day04::AdventPuzzle::part2(&input),
-        (5, 1) => day05::AdventPuzzle::part1(&input),
-        (5, 2) => day05::AdventPuzzle::part2(&input),
-        (6, 1) => day06::AdventPuzzle::part1(&input),
-        (6, 2) => day06::AdventPuzzle::part2(&input),
-        (7, 1) => day07::AdventPuzzle::part1(&input),
-        (7, 2) => day07::AdventPuzzle::part2(&input),
-        (8, 1) => day08::AdventPuzzle::part1(&input),
-        (8, 2) => day08::AdventPuzzle::part2(&input),
-        (9, 1) => day09::AdventPuzzle::part1(&input),
-        (9, 2) => day09::AdventPuzzle::part2(&input),
-        (10, 1) => day10::AdventPuzzle::part1(&input),
-        (10, 2) => day10::AdventPuzzle::part2(&input),
-        (11, 1) => day11::AdventPuzzle::part1(&input),
-        (11, 2) => day11::AdventPuzzle::part2(&input),
-        (12, 1) => day12::AdventPuzzle::part1(&input),
-        (12, 2) => day12::AdventPuzzle::part2(&input),
-        (13, 1) => day13::AdventPuzzle::part1(&input),
-        (13, 2) => day13::AdventPuzzle::part2(&input),
-        (14, 1) => day14::AdventPuzzle::part1(&input),
-        (14, 2) => day14::AdventPuzzle::part2(&input),
-        (15, 1) => day15::AdventPuzzle::part1(&input),
-        (15, 2) => day15::AdventPuzzle::part2(&input),
-        (16, 1) => day16::AdventPuzzle::part1(&input),
-        (16, 2) => day16::AdventPuzzle::part2(&input),
-        (17, 1) => day17::AdventPuzzle::part1(&input),
-        (17, 2) => day17::AdventPuzzle::part2(&input),
-        (18, 1) => day18::AdventPuzzle::part1(&input),
-        (18, 2) => day18::AdventPuzzle::part2(&input),
-        (19, 1) => day19::AdventPuzzle::part1(&input),
-        (19, 2) => day19::AdventPuzzle::part2(&input),
-        (20, 1) => day20::AdventPuzzle::part1(&input),
-        (20, 2) => day20::AdventPuzzle::part2(&input),
-        (21, 1) => day21::AdventPuzzle::part1(&input),
-        (21, 2) => day21::AdventPuzzle::part2(&input),
-        (22, 1) => day22::AdventPuzzle::part1(&input),
-        (22, 2) => day22::AdventPuzzle::part2(&input),
-        (23, 1) => day23::AdventPuzzle::part1(&input),
-        (23, 2) => day23::AdventPuzzle::part2(&input),
-        (24, 1) => day24::AdventPuzzle::part1(&input),
-        (24, 2) => day24::AdventPuzzle::part2(&input),
-        (25, 1) => day25::AdventPuzzle::part1(&input),
-        (25, 2) => day25::AdventPuzzle::part2(&input),
+    let result = match (opt.day, opt.part, opt.blinks) {
+        (1, 1, _) => day01::AdventPuzzle::part1(&input),
+        (1, 2, _) => day01::AdventPuzzle::part2(&input),
+        (2, 1, _) => day02::AdventPuzzle::part1(&input),
+        (2, 2, _) => day02::AdventPuzzle::part2(&input),
+        (3, 1, _) => day03::AdventPuzzle::part1(&input),
+        (3, 2, _) => day03::AdventPuzzle::part2(&input),
+        (4, 1, _) => day04::AdventPuzzle::part1(&input),
+        (4, 2, _) => day04::AdventPuzzle::part2(&input),
+        (5, 1, _) => day05::AdventPuzzle::part1(&input),
+        (5, 2, _) => day05::AdventPuzzle::part2(&input),
+        (6, 1, _) => day06::AdventPuzzle::part1(&input),
+        (6, 2, _) => day06::AdventPuzzle::part2(&input),
+        (7, 1, _) => day07::AdventPuzzle::part1(&input),
+        (7, 2, _) => day07::AdventPuzzle::part2(&input),
+        (8, 1, _) => day08::AdventPuzzle::part1(&input),
+        (8, 2, _) => day08::AdventPuzzle::part2(&input),
+        (9, 1, _) => day09::AdventPuzzle::part1(&input),
+        (9, 2, _) => day09::AdventPuzzle::part2(&input),
+        (10, 1, _) => day10::AdventPuzzle::part1(&input),
+        (10, 2, _) => day10::AdventPuzzle::part2(&input),
+        (11, _, Some(blinks)) => match day11::count_stones(&input, blinks) {
+            Ok(count) => count.to_string(),
+            Err(error) => {
+                eprintln!("{error}");
+                exit(1);
+            }
+        },
+        (11, 1, _) => day11::AdventPuzzle::part1(&input),
+        (11, 2, _) => day11::AdventPuzzle::part2(&input),
+        (12, 1, _) => day12::AdventPuzzle::part1(&input),
+        (12, 2, _) => day12::AdventPuzzle::part2(&input),
+        (13, 1, _) => day13::AdventPuzzle::part1(&input),
+        (13, 2, _) => day13::AdventPuzzle::part2(&input),
+        (14, 1, _) => day14::AdventPuzzle::part1(&input),
+        (14, 2, _) => day14::AdventPuzzle::part2(&input),
+        (15, 1, _) => day15::AdventPuzzle::part1(&input),
+        (15, 2, _) => day15::AdventPuzzle::part2(&input),
+        (16, 1, _) => day16::AdventPuzzle::part1(&input),
+        (16, 2, _) => day16::AdventPuzzle::part2(&input),
+        (17, 1, _) => day17::AdventPuzzle::part1(&input),
+        (17, 2, _) => day17::AdventPuzzle::part2(&input),
+        (18, 1, _) => day18::AdventPuzzle::part1(&input),
+        (18, 2, _) => day18::AdventPuzzle::part2(&input),
+        (19, 1, _) => day19::AdventPuzzle::part1(&input),
+        (19, 2, _) => day19::AdventPuzzle::part2(&input),
+        (20, 1, _) => day20::AdventPuzzle::part1(&input),
+        (20, 2, _) => day20::AdventPuzzle::part2(&input),
+        (21, 1, _) => day21::AdventPuzzle::part1(&input),
+        (21, 2, _) => day21::AdventPuzzle::part2(&input),
+        (22, 1, _) => day22::AdventPuzzle::part1(&input),
+        (22, 2, _) => day22::AdventPuzzle::part2(&input),
+        (23, 1, _) => day23::AdventPuzzle::part1(&input),
+        (23, 2, _) => day23::AdventPuzzle::part2(&input),
+        (24, 1, _) => day24::AdventPuzzle::part1(&input),
+        (24, 2, _) => day24::AdventPuzzle::part2(&input),
+        (25, 1, _) => day25::AdventPuzzle::part1(&input),
+        (25, 2, _) => day25::AdventPuzzle::part2(&input),
         _ => {
             eprintln!("Day {} part {} not found", opt.day, opt.part);
             exit(1);
         }
     };
     let end = Instant::now();
+
+    if opt.verbose {
+        let explanation = match (opt.day, opt.part) {
+            (1, 1) => day01::AdventPuzzle::explain_part1(&input),
+            (1, 2) => day01::AdventPuzzle::explain_part2(&input),
+            (2, 1) => day02::AdventPuzzle::explain_part1(&input),
+            (2, 2) => day02::AdventPuzzle::explain_part2(&input),
+            (3, 1) => day03::AdventPuzzle::explain_part1(&input),
+            (3, 2) => day03::AdventPuzzle::explain_part2(&input),
+            (5, 1) => day05::AdventPuzzle::explain_part1(&input),
+            (5, 2) => day05::AdventPuzzle::explain_part2(&input),
+            (8, 1) => day08::AdventPuzzle::explain_part1(&input),
+            (8, 2) => day08::AdventPuzzle::explain_part2(&input),
+            (9, 1) => day09::AdventPuzzle::explain_part1(&input),
+            (9, 2) => day09::AdventPuzzle::explain_part2(&input),
+            (10, 1) => day10::AdventPuzzle::explain_part1(&input),
+            (10, 2) => day10::AdventPuzzle::explain_part2(&input),
+            (13, 1) => day13::AdventPuzzle::explain_part1(&input),
+            (13, 2) => day13::AdventPuzzle::explain_part2(&input),
+            (14, 1) => day14::AdventPuzzle::explain_part1(&input),
+            (14, 2) => day14::AdventPuzzle::explain_part2(&input),
+            (15, 1) => day15::AdventPuzzle::explain_part1(&input),
+            (15, 2) => day15::AdventPuzzle::explain_part2(&input),
+            (16, 2) => day16::AdventPuzzle::explain_part2(&input),
+            (17, 1) => day17::AdventPuzzle::explain_part1(&input),
+            (17, 2) => day17::AdventPuzzle::explain_part2(&input),
+            (19, 1) => day19::AdventPuzzle::explain_part1(&input),
+            _ => None,
+        };
+        match explanation {
+            Some(explanation) => println!("{explanation}"),
+            None => eprintln!("No verbose output for day {} part {}", opt.day, opt.part),
+        }
+    }
+
     let duration = end - start;
     let seconds = duration.as_secs();
     let sub_millis = duration.subsec_millis();
     let sub_micros = duration.subsec_micros() - (sub_millis * 1000);
     let sub_nanos = (duration.subsec_nanos() - (sub_millis * 1_000_000)) - (sub_micros * 1000);
-    println!("Answer for day {} part {} is:", opt.day, opt.part);
+    match opt.blinks {
+        Some(blinks) => println!("Answer for day {} after {blinks} blinks is:", opt.day),
+        None => println!("Answer for day {} part {} is:", opt.day, opt.part),
+    }
     println!("{result}");
     println!("Time taken: {seconds}s {sub_millis}ms {sub_micros}µs {sub_nanos}ns");
 }
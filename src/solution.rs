@@ -2,4 +2,14 @@ pub trait Solution {
     fn part1(_input: &str) -> String;
 
     fn part2(_input: &str) -> String;
+
+    /// Extra detail on how part 1 was solved, printed when running with `--verbose`
+    fn explain_part1(_input: &str) -> Option<String> {
+        None
+    }
+
+    /// Extra detail on how part 2 was solved, printed when running with `--verbose`
+    fn explain_part2(_input: &str) -> Option<String> {
+        None
+    }
 }